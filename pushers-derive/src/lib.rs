@@ -0,0 +1,98 @@
+//! Derive macro for `pushers::PusherEvent`
+//!
+//! Use it through the `derive` feature of `pushers` rather than depending on this crate directly.
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, LitStr, parse_macro_input};
+
+/// Maximum length of an event name accepted by the Pusher API
+const MAX_EVENT_NAME_LENGTH: usize = 200;
+
+/// Implements `pushers::PusherEvent` for a `Serialize` type
+///
+/// The event name comes from `#[pusher(name = "...")]`, or defaults to the type name in
+/// kebab-case (`OrderUpdated` becomes `order-updated`).
+#[proc_macro_derive(PusherEvent, attributes(pusher))]
+pub fn derive_pusher_event(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let mut name = None;
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("pusher")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                let value: LitStr = meta.value()?.parse()?;
+                if name.is_some() {
+                    return Err(meta.error("duplicate `name` attribute"));
+                }
+                name = Some((value.value(), value.span()));
+                Ok(())
+            } else {
+                Err(meta.error("unsupported attribute, expected `name = \"...\"`"))
+            }
+        })?;
+    }
+
+    let (name, span) =
+        name.unwrap_or_else(|| (kebab_case(&input.ident.to_string()), input.ident.span()));
+    if name.is_empty() || name.len() > MAX_EVENT_NAME_LENGTH {
+        return Err(syn::Error::new(
+            span,
+            format!(
+                "event name must be 1-{} characters (got {})",
+                MAX_EVENT_NAME_LENGTH,
+                name.len()
+            ),
+        ));
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::pushers::PusherEvent for #ident #ty_generics #where_clause {
+            const NAME: &'static str = #name;
+        }
+    })
+}
+
+/// Converts a type name such as `OrderUpdated` to `order-updated`
+fn kebab_case(ident: &str) -> String {
+    let chars: Vec<char> = ident.trim_start_matches("r#").chars().collect();
+    let mut name = String::with_capacity(chars.len() + 4);
+    for (i, &c) in chars.iter().enumerate() {
+        if c == '_' {
+            name.push('-');
+            continue;
+        }
+        if c.is_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+            if prev.is_lowercase()
+                || prev.is_ascii_digit()
+                || (prev.is_uppercase() && next_is_lower)
+            {
+                name.push('-');
+            }
+        }
+        name.extend(c.to_lowercase());
+    }
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kebab_case() {
+        assert_eq!(kebab_case("OrderUpdated"), "order-updated");
+        assert_eq!(kebab_case("HTTPRequestSent"), "http-request-sent");
+        assert_eq!(kebab_case("Order2Shipped"), "order2-shipped");
+        assert_eq!(kebab_case("order_updated"), "order-updated");
+    }
+}
@@ -0,0 +1,137 @@
+//! actix-web extractors
+//!
+//! The [`Webhook`] extractor needs a [`Pusher`] registered as app data, either
+//! directly or wrapped in `web::Data`. Requests whose signature does not verify
+//! are rejected with `401 Unauthorized`.
+
+use crate::{AuthRequest, Pusher, Webhook};
+use actix_web::dev::Payload;
+use actix_web::{Error, FromRequest, HttpRequest, error, web};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::pin::Pin;
+
+type ExtractFuture<T> = Pin<Box<dyn Future<Output = Result<T, Error>>>>;
+
+/// Finds the `Pusher` client registered on the app
+fn app_pusher(req: &HttpRequest) -> Option<Pusher> {
+    req.app_data::<web::Data<Pusher>>()
+        .map(|data| data.get_ref().clone())
+        .or_else(|| req.app_data::<Pusher>().cloned())
+}
+
+/// Collects request headers, keeping the first value of repeated headers
+fn header_map(req: &HttpRequest) -> BTreeMap<String, String> {
+    let mut headers = BTreeMap::new();
+    for (name, value) in req.headers() {
+        if let Ok(value) = value.to_str() {
+            headers
+                .entry(name.as_str().to_string())
+                .or_insert_with(|| value.to_string());
+        }
+    }
+    headers
+}
+
+impl FromRequest for Webhook {
+    type Error = Error;
+    type Future = ExtractFuture<Self>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let pusher = app_pusher(req);
+        let headers = header_map(req);
+        let body = web::Bytes::from_request(req, payload);
+
+        Box::pin(async move {
+            let pusher = pusher.ok_or_else(|| {
+                error::ErrorInternalServerError("Pusher client is not registered as app data")
+            })?;
+            let body = body.await?;
+
+            let webhook = pusher.webhook_from_bytes(&headers, &body);
+            if !webhook.is_valid(None) {
+                return Err(error::ErrorUnauthorized("Invalid webhook"));
+            }
+            Ok(webhook)
+        })
+    }
+}
+
+impl FromRequest for AuthRequest {
+    type Error = Error;
+    type Future = ExtractFuture<Self>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let content_type = req
+            .headers()
+            .get("content-type")
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let body = web::Bytes::from_request(req, payload);
+
+        Box::pin(async move {
+            let body = body.await?;
+            AuthRequest::parse(content_type.as_deref(), &body).map_err(error::ErrorBadRequest)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+    use actix_web::test::TestRequest;
+
+    fn test_pusher() -> Pusher {
+        let config = Config::builder()
+            .app_id("123")
+            .key("test_key")
+            .secret("test_secret")
+            .build()
+            .unwrap();
+        Pusher::new(config).unwrap()
+    }
+
+    #[actix_web::rt::test]
+    async fn test_webhook_extractor() {
+        let pusher = test_pusher();
+        let body = r#"{"time_ms":1234567890,"events":[]}"#;
+        let signature = pusher.config().token().sign(body);
+
+        let (req, mut payload) = TestRequest::post()
+            .app_data(web::Data::new(pusher))
+            .insert_header(("content-type", "application/json"))
+            .insert_header(("x-pusher-key", "test_key"))
+            .insert_header(("x-pusher-signature", signature))
+            .set_payload(body)
+            .to_http_parts();
+        let webhook = <Webhook as FromRequest>::from_request(&req, &mut payload)
+            .await
+            .unwrap();
+        assert_eq!(webhook.body(), Some(body));
+
+        let (req, mut payload) = TestRequest::post()
+            .app_data(web::Data::new(test_pusher()))
+            .insert_header(("content-type", "application/json"))
+            .insert_header(("x-pusher-key", "test_key"))
+            .insert_header(("x-pusher-signature", "bad"))
+            .set_payload(body)
+            .to_http_parts();
+        assert!(
+            <Webhook as FromRequest>::from_request(&req, &mut payload)
+                .await
+                .is_err()
+        );
+    }
+
+    #[actix_web::rt::test]
+    async fn test_channel_auth_extractor() {
+        let (req, mut payload) = TestRequest::post()
+            .insert_header(("content-type", "application/x-www-form-urlencoded"))
+            .set_payload("socket_id=123.456&channel_name=private-test")
+            .to_http_parts();
+        let auth = AuthRequest::from_request(&req, &mut payload).await.unwrap();
+        assert_eq!(auth.socket_id, "123.456");
+        assert_eq!(auth.channel_name, "private-test");
+    }
+}
@@ -0,0 +1,372 @@
+//! Audit trail of triggered events
+
+use crate::json::{self, Value};
+use crate::transport::Response;
+use crate::{PusherError, Result};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+use std::time::SystemTime;
+use zeroize::Zeroizing;
+
+/// Outcome of publishing an event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuditOutcome {
+    Success { status: u16 },
+    Failure { error: String },
+}
+
+/// Record of a single published event
+#[derive(Debug, Clone)]
+pub struct AuditRecord {
+    pub timestamp: SystemTime,
+    pub channels: Vec<String>,
+    pub event: String,
+    /// Size in bytes of the `data` field that was sent
+    pub payload_size: usize,
+    /// Payload after redaction; `None` unless a redaction function chose to keep it
+    pub payload: Option<String>,
+    pub outcome: AuditOutcome,
+}
+
+/// What part of an event payload is written to logs
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum RedactionPolicy {
+    /// Omit the payload; only its size is logged
+    #[default]
+    Drop,
+    /// Log the HMAC-SHA256 of the payload, e.g. to correlate identical events
+    ///
+    /// The key keeps short or guessable payloads from being recovered from the log by
+    /// hashing candidates; use one secret per deployment and keep it out of the logs.
+    Hash(HashKey),
+    /// Log only these top-level fields of a JSON object payload
+    AllowFields(Vec<String>),
+}
+
+impl RedactionPolicy {
+    /// Applies the policy to a payload, returning what may be logged
+    ///
+    /// Usable as an [`audit_redaction`](crate::ConfigBuilder::audit_redaction) function.
+    /// `AllowFields` returns `None` for payloads that are not JSON objects.
+    pub fn apply(&self, payload: &str) -> Option<String> {
+        match self {
+            RedactionPolicy::Drop => None,
+            RedactionPolicy::Hash(key) => {
+                let mut mac =
+                    Hmac::<Sha256>::new_from_slice(&key.0).expect("HMAC can take key of any size");
+                mac.update(payload.as_bytes());
+                Some(format!(
+                    "hmac-sha256:{}",
+                    hex::encode(mac.finalize().into_bytes())
+                ))
+            }
+            RedactionPolicy::AllowFields(fields) => {
+                let mut object: BTreeMap<String, Value> = json::from_str(payload).ok()?;
+                object.retain(|key, _| fields.contains(key));
+                json::to_string(&object).ok()
+            }
+        }
+    }
+}
+
+/// Secret key for [`RedactionPolicy::Hash`]
+#[derive(Clone, PartialEq, Eq)]
+pub struct HashKey(Zeroizing<Vec<u8>>);
+
+impl HashKey {
+    pub fn new(key: impl Into<Vec<u8>>) -> Self {
+        Self(Zeroizing::new(key.into()))
+    }
+}
+
+impl fmt::Debug for HashKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "HashKey(..)")
+    }
+}
+
+type AuditFn = dyn Fn(&AuditRecord) + Send + Sync;
+type RedactFn = dyn Fn(&str) -> Option<String> + Send + Sync;
+
+/// Audit hook and redaction function stored in the configuration
+#[derive(Clone, Default)]
+pub(crate) struct Auditor {
+    hook: Option<Arc<AuditFn>>,
+    redact: Option<Arc<RedactFn>>,
+    log_policy: Option<RedactionPolicy>,
+}
+
+impl Auditor {
+    pub(crate) fn set_hook(&mut self, hook: Arc<AuditFn>) {
+        self.hook = Some(hook);
+    }
+
+    pub(crate) fn set_redaction(&mut self, redact: Arc<RedactFn>) {
+        self.redact = Some(redact);
+    }
+
+    pub(crate) fn set_log_policy(&mut self, policy: RedactionPolicy) {
+        self.log_policy = Some(policy);
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.hook.is_some() || self.log_policy.is_some()
+    }
+
+    /// Redacts a payload written outside audit records, dropping it unless an audit
+    /// redaction or log policy is configured
+    #[cfg(feature = "debug-recorder")]
+    pub(crate) fn redact(&self, payload: &str) -> Option<String> {
+        match (&self.redact, &self.log_policy) {
+            (Some(redact), _) => redact(payload),
+            (None, Some(policy)) => policy.apply(payload),
+            (None, None) => None,
+        }
+    }
+
+    /// Emits an audit record for an event if a hook is configured
+    pub(crate) fn record(
+        &self,
+        channels: &[String],
+        event: &str,
+        payload: &str,
+        result: &Result<Response>,
+    ) {
+        let outcome = match result {
+            Ok(response) => AuditOutcome::Success {
+                status: response.status().as_u16(),
+            },
+            Err(e) => AuditOutcome::Failure {
+                error: e.to_string(),
+            },
+        };
+        self.emit(channels.to_vec(), event, payload, outcome);
+    }
+
+    /// Emits an audit record for every `(channel, event, payload)` of a batch
+    pub(crate) fn record_batch(&self, events: &[(&str, &str, &str)], result: &Result<Response>) {
+        for (index, (channel, event, payload)) in events.iter().enumerate() {
+            let outcome = match result {
+                Ok(response) => AuditOutcome::Success {
+                    status: response.status().as_u16(),
+                },
+                // Events outside the failed set were delivered by another sub-batch
+                Err(PusherError::Batch { failed_indices, .. })
+                    if !failed_indices.contains(&index) =>
+                {
+                    AuditOutcome::Success { status: 200 }
+                }
+                Err(e) => AuditOutcome::Failure {
+                    error: e.to_string(),
+                },
+            };
+            self.emit(vec![channel.to_string()], event, payload, outcome);
+        }
+    }
+
+    fn emit(&self, channels: Vec<String>, event: &str, payload: &str, outcome: AuditOutcome) {
+        if let Some(ref policy) = self.log_policy {
+            log_event(&channels, event, payload, &outcome, policy);
+        }
+
+        let Some(ref hook) = self.hook else {
+            return;
+        };
+
+        hook(&AuditRecord {
+            timestamp: SystemTime::now(),
+            channels,
+            event: event.to_string(),
+            payload_size: payload.len(),
+            payload: self.redact.as_ref().and_then(|redact| redact(payload)),
+            outcome,
+        });
+    }
+}
+
+/// Writes a triggered event to the `pushers::events` tracing target
+fn log_event(
+    channels: &[String],
+    event: &str,
+    payload: &str,
+    outcome: &AuditOutcome,
+    policy: &RedactionPolicy,
+) {
+    let payload_logged = policy.apply(payload);
+    match outcome {
+        AuditOutcome::Success { status } => tracing::info!(
+            target: "pushers::events",
+            channels = ?channels,
+            event,
+            payload_size = payload.len(),
+            payload = payload_logged.as_deref(),
+            status,
+            "event triggered"
+        ),
+        AuditOutcome::Failure { error } => tracing::warn!(
+            target: "pushers::events",
+            channels = ?channels,
+            event,
+            payload_size = payload.len(),
+            payload = payload_logged.as_deref(),
+            error = error.as_str(),
+            "event trigger failed"
+        ),
+    }
+}
+
+impl fmt::Debug for Auditor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Auditor")
+            .field("hook", &self.hook.is_some())
+            .field("redact", &self.redact.is_some())
+            .field("log_policy", &self.log_policy)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    fn recording_auditor() -> (Auditor, Arc<Mutex<Vec<AuditRecord>>>) {
+        let records = Arc::new(Mutex::new(Vec::new()));
+        let sink = records.clone();
+        let mut auditor = Auditor::default();
+        auditor.set_hook(Arc::new(move |record: &AuditRecord| {
+            sink.lock().unwrap().push(record.clone())
+        }));
+        (auditor, records)
+    }
+
+    #[test]
+    fn test_payload_redacted_by_default() {
+        let (auditor, records) = recording_auditor();
+        let result = Err(PusherError::Validation {
+            message: "failed".to_string(),
+        });
+
+        auditor.record(&["test".to_string()], "event", "secret", &result);
+
+        let records = records.lock().unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].payload_size, 6);
+        assert_eq!(records[0].payload, None);
+        assert!(matches!(records[0].outcome, AuditOutcome::Failure { .. }));
+    }
+
+    #[test]
+    fn test_redaction_function() {
+        let (mut auditor, records) = recording_auditor();
+        auditor.set_redaction(Arc::new(|payload: &str| {
+            Some(payload.replace("secret", "***"))
+        }));
+        let result = Err(PusherError::Validation {
+            message: "failed".to_string(),
+        });
+
+        auditor.record(&["test".to_string()], "event", "my secret", &result);
+
+        assert_eq!(
+            records.lock().unwrap()[0].payload,
+            Some("my ***".to_string())
+        );
+    }
+
+    #[test]
+    fn test_batch_partial_failure() {
+        let (auditor, records) = recording_auditor();
+        let result = Err(PusherError::Batch {
+            message: "1 of 2 events rejected as too large".to_string(),
+            failed_indices: vec![1],
+        });
+
+        auditor.record_batch(&[("a", "event", "1"), ("b", "event", "2")], &result);
+
+        let records = records.lock().unwrap();
+        assert_eq!(records[0].outcome, AuditOutcome::Success { status: 200 });
+        assert!(matches!(records[1].outcome, AuditOutcome::Failure { .. }));
+    }
+
+    #[test]
+    fn test_redaction_policies() {
+        let payload = r#"{"order_id": 42, "card": "4111", "status": "paid"}"#;
+
+        assert_eq!(RedactionPolicy::Drop.apply(payload), None);
+        // RFC 4231 test case 2
+        let hash = RedactionPolicy::Hash(HashKey::new("Jefe"));
+        assert_eq!(
+            hash.apply("what do ya want for nothing?"),
+            Some(
+                "hmac-sha256:5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+                    .to_string()
+            )
+        );
+        assert_ne!(
+            RedactionPolicy::Hash(HashKey::new("other")).apply("abc"),
+            hash.apply("abc")
+        );
+        assert_eq!(format!("{:?}", hash), "Hash(HashKey(..))");
+
+        let allow =
+            RedactionPolicy::AllowFields(vec!["order_id".to_string(), "status".to_string()]);
+        let kept: Value = json::from_str(&allow.apply(payload).unwrap()).unwrap();
+        assert_eq!(kept, json::json!({"order_id": 42, "status": "paid"}));
+        assert_eq!(allow.apply("not json"), None);
+        assert_eq!(allow.apply("[1, 2]"), None);
+    }
+
+    /// Collects formatted tracing output
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl std::io::Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_log_policy_enables_auditor() {
+        let mut auditor = Auditor::default();
+        assert!(!auditor.is_enabled());
+        let policy = RedactionPolicy::Hash(HashKey::new("deployment-secret"));
+        auditor.set_log_policy(policy.clone());
+        assert!(auditor.is_enabled());
+
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(move || writer.clone())
+            .with_ansi(false)
+            .finish();
+        tracing::subscriber::with_default(subscriber, || {
+            auditor.record(
+                &["test".to_string()],
+                "event",
+                "card=4111111111111111",
+                &Err(PusherError::Validation {
+                    message: "failed".to_string(),
+                }),
+            );
+        });
+
+        let output = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        assert!(output.contains("event trigger failed"), "{}", output);
+        assert!(!output.contains("4111111111111111"), "{}", output);
+        assert!(
+            output.contains(&policy.apply("card=4111111111111111").unwrap()),
+            "{}",
+            output
+        );
+    }
+}
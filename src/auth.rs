@@ -1,5 +1,5 @@
-use crate::{Token, util};
-use sonic_rs::Value;
+use crate::json::{self, Value};
+use crate::{PusherError, Token, util};
 
 /// Authentication data for socket connections
 #[derive(Debug, serde::Serialize)]
@@ -12,12 +12,182 @@ pub struct SocketAuth {
 }
 
 /// User authentication data
-#[derive(Debug, serde::Serialize)]
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub struct UserAuth {
     pub auth: String,
     pub user_data: String,
 }
 
+impl UserAuth {
+    /// Parses the signed `user_data` string
+    pub fn parse_user_data<T: serde::de::DeserializeOwned>(&self) -> crate::Result<UserData<T>> {
+        UserData::parse(&self.user_data)
+    }
+}
+
+/// The `user_data` object of user authentication, generic over `user_info`
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct UserData<T = Value> {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_info: Option<T>,
+    /// User IDs whose online status this user receives
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub watchlist: Vec<String>,
+}
+
+impl<T> UserData<T> {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            user_info: None,
+            watchlist: Vec::new(),
+        }
+    }
+
+    /// Sets the `user_info` visible to other users
+    pub fn with_user_info(mut self, user_info: T) -> Self {
+        self.user_info = Some(user_info);
+        self
+    }
+
+    /// Sets the watchlist
+    pub fn with_watchlist<I, S>(mut self, watchlist: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.watchlist = watchlist.into_iter().map(Into::into).collect();
+        self
+    }
+}
+
+impl<T: serde::de::DeserializeOwned> UserData<T> {
+    /// Parses a `user_data` string
+    pub fn parse(user_data: &str) -> crate::Result<Self> {
+        json::from_str(user_data).map_err(|e| PusherError::Validation {
+            message: format!("Invalid user_data: {}", e),
+        })
+    }
+}
+
+/// Member data sent as `channel_data` when authorizing a presence channel
+///
+/// `user_info` defaults to an untyped JSON value but can be any serde type, so the same struct
+/// builds the payload for authorization and reads it back with [`PresenceMember::parse`].
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PresenceMember<T = Value> {
+    pub user_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_info: Option<T>,
+}
+
+impl PresenceMember {
+    pub fn new(user_id: impl Into<String>) -> Self {
+        Self {
+            user_id: user_id.into(),
+            user_info: None,
+        }
+    }
+
+    /// Sets the `user_info` visible to other members
+    pub fn with_user_info(mut self, user_info: Value) -> Self {
+        self.user_info = Some(user_info);
+        self
+    }
+}
+
+impl<T> PresenceMember<T> {
+    /// Creates a member with typed `user_info`
+    pub fn with_info(user_id: impl Into<String>, user_info: T) -> Self {
+        Self {
+            user_id: user_id.into(),
+            user_info: Some(user_info),
+        }
+    }
+
+    /// Creates a member known only by its ID, as reported by member webhooks
+    pub fn without_info(user_id: impl Into<String>) -> Self {
+        Self {
+            user_id: user_id.into(),
+            user_info: None,
+        }
+    }
+}
+
+impl<T: serde::Serialize> PresenceMember<T> {
+    /// Serializes the member as a presence `channel_data` string
+    pub fn to_channel_data(&self) -> crate::Result<String> {
+        Ok(json::to_string(self)?)
+    }
+}
+
+impl<T: serde::de::DeserializeOwned> PresenceMember<T> {
+    /// Parses a presence `channel_data` string
+    pub fn parse(channel_data: &str) -> crate::Result<Self> {
+        json::from_str(channel_data).map_err(|e| PusherError::Validation {
+            message: format!("Invalid presence channel_data: {}", e),
+        })
+    }
+}
+
+impl SocketAuth {
+    /// Parses `channel_data` as presence member data, if present
+    pub fn presence_member<T: serde::de::DeserializeOwned>(
+        &self,
+    ) -> crate::Result<Option<PresenceMember<T>>> {
+        self.channel_data
+            .as_deref()
+            .map(PresenceMember::parse)
+            .transpose()
+    }
+}
+
+/// Channel authorization request body sent by Pusher client libraries
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize)]
+pub struct AuthRequest {
+    pub socket_id: String,
+    pub channel_name: String,
+}
+
+impl AuthRequest {
+    /// Parses the body according to its content type (JSON, otherwise form-encoded)
+    pub fn parse(content_type: Option<&str>, body: &[u8]) -> crate::Result<Self> {
+        match content_type {
+            Some(ct) if ct.starts_with("application/json") => Self::from_json(body),
+            _ => Self::from_form(body),
+        }
+    }
+
+    /// Parses an `application/x-www-form-urlencoded` body
+    pub fn from_form(body: &[u8]) -> crate::Result<Self> {
+        let mut socket_id = None;
+        let mut channel_name = None;
+        for (key, value) in url::form_urlencoded::parse(body) {
+            match key.as_ref() {
+                "socket_id" => socket_id = Some(value.into_owned()),
+                "channel_name" => channel_name = Some(value.into_owned()),
+                _ => {}
+            }
+        }
+
+        let missing = |field: &str| PusherError::Validation {
+            message: format!("Missing {} in auth request", field),
+        };
+        Ok(Self {
+            socket_id: socket_id.ok_or_else(|| missing("socket_id"))?,
+            channel_name: channel_name.ok_or_else(|| missing("channel_name"))?,
+        })
+    }
+
+    /// Parses a JSON body
+    pub fn from_json(body: &[u8]) -> crate::Result<Self> {
+        json::from_slice(body).map_err(|e| PusherError::Validation {
+            message: format!("Invalid auth request: {}", e),
+        })
+    }
+}
+
 /// Gets socket signature for channel authorization
 pub fn get_socket_signature(
     pusher: &crate::Pusher,
@@ -26,18 +196,38 @@ pub fn get_socket_signature(
     socket_id: &str,
     data: Option<&Value>,
 ) -> crate::Result<SocketAuth> {
+    let (auth_string, channel_data) = channel_auth_string(channel, socket_id, data)?;
+    let signature = token.sign(&auth_string);
+    socket_auth(pusher, &token.key, &signature, channel, channel_data)
+}
+
+/// Builds the string signed for channel authorization, along with the serialized channel data
+pub(crate) fn channel_auth_string(
+    channel: &str,
+    socket_id: &str,
+    data: Option<&Value>,
+) -> crate::Result<(String, Option<String>)> {
     let mut signature_data = vec![socket_id.to_string(), channel.to_string()];
     let mut channel_data = None;
 
     if let Some(data) = data {
-        let serialized = sonic_rs::to_string(data)?;
+        let serialized = json::to_string(data)?;
         signature_data.push(serialized.clone());
         channel_data = Some(serialized);
     }
 
-    let auth_string = signature_data.join(":");
-    let signature = token.sign(&auth_string);
-    let auth = format!("{}:{}", token.key, signature);
+    Ok((signature_data.join(":"), channel_data))
+}
+
+/// Assembles the channel auth response from a computed signature
+pub(crate) fn socket_auth(
+    pusher: &crate::Pusher,
+    key: &str,
+    signature: &str,
+    channel: &str,
+    channel_data: Option<String>,
+) -> crate::Result<SocketAuth> {
+    let auth = format!("{}:{}", key, signature);
 
     let mut result = SocketAuth {
         auth,
@@ -49,7 +239,7 @@ pub fn get_socket_signature(
     if util::is_encrypted_channel(channel) {
         #[cfg(feature = "encryption")]
         {
-            if pusher.config().encryption_master_key().is_none() {
+            if !pusher.has_encryption_master_key() {
                 return Err(crate::PusherError::Encryption {
                     message: "Cannot generate shared_secret because encryptionMasterKey is not set"
                         .to_string(),
@@ -59,7 +249,7 @@ pub fn get_socket_signature(
             let shared_secret = pusher.channel_shared_secret(channel)?;
             result.shared_secret = Some(base64::Engine::encode(
                 &base64::engine::general_purpose::STANDARD,
-                &shared_secret,
+                &shared_secret[..],
             ));
         }
 
@@ -75,13 +265,12 @@ pub fn get_socket_signature(
 }
 
 /// Gets socket signature for user authentication
-pub fn get_socket_signature_for_user(
+pub fn get_socket_signature_for_user<U: serde::Serialize + ?Sized>(
     token: &Token,
     socket_id: &str,
-    user_data: &Value,
+    user_data: &U,
 ) -> crate::Result<UserAuth> {
-    let serialized_user_data = sonic_rs::to_string(user_data)?;
-    let signature_string = format!("{}::user::{}", socket_id, serialized_user_data);
+    let (signature_string, serialized_user_data) = user_auth_string(socket_id, user_data)?;
     let signature = token.sign(&signature_string);
 
     Ok(UserAuth {
@@ -90,10 +279,76 @@ pub fn get_socket_signature_for_user(
     })
 }
 
+/// Builds the string signed for user authentication, along with the serialized user data
+///
+/// The data is serialized once, so the returned `user_data` is exactly the signed string.
+pub(crate) fn user_auth_string<U: serde::Serialize + ?Sized>(
+    socket_id: &str,
+    user_data: &U,
+) -> crate::Result<(String, String)> {
+    let serialized_user_data = json::to_string(user_data)?;
+    let signature_string = format!("{}::user::{}", socket_id, serialized_user_data);
+    Ok((signature_string, serialized_user_data))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use sonic_rs::json;
+    use crate::json::json;
+
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct UserInfo {
+        name: String,
+    }
+
+    #[test]
+    fn test_presence_member_round_trip() {
+        let member = PresenceMember::with_info(
+            "user-1",
+            UserInfo {
+                name: "Alice".to_string(),
+            },
+        );
+        let channel_data = member.to_channel_data().unwrap();
+        assert_eq!(
+            channel_data,
+            r#"{"user_id":"user-1","user_info":{"name":"Alice"}}"#
+        );
+        assert_eq!(
+            PresenceMember::<UserInfo>::parse(&channel_data).unwrap(),
+            member
+        );
+
+        let untyped = PresenceMember::parse(r#"{"user_id":"user-2"}"#).unwrap();
+        assert_eq!(untyped, PresenceMember::new("user-2"));
+        assert!(PresenceMember::<UserInfo>::parse(r#"{"user_info":{}}"#).is_err());
+
+        let auth = SocketAuth {
+            auth: "key:signature".to_string(),
+            channel_data: Some(channel_data),
+            shared_secret: None,
+        };
+        assert_eq!(auth.presence_member::<UserInfo>().unwrap(), Some(member));
+    }
+
+    #[test]
+    fn test_parse_auth_request() {
+        let expected = AuthRequest {
+            socket_id: "123.456".to_string(),
+            channel_name: "private-test".to_string(),
+        };
+
+        let form = b"socket_id=123.456&channel_name=private-test";
+        assert_eq!(AuthRequest::parse(None, form).unwrap(), expected);
+
+        let body = br#"{"socket_id": "123.456", "channel_name": "private-test"}"#;
+        assert_eq!(
+            AuthRequest::parse(Some("application/json"), body).unwrap(),
+            expected
+        );
+
+        assert!(AuthRequest::from_form(b"socket_id=123.456").is_err());
+    }
 
     #[test]
     fn test_get_socket_signature_for_user() {
@@ -106,6 +361,28 @@ mod tests {
         assert!(result.user_data.contains("123"));
     }
 
+    #[test]
+    fn test_user_data_round_trip() {
+        let user_data = UserData::new("123").with_user_info(UserInfo {
+            name: "Alice".to_string(),
+        });
+        let result = get_socket_signature_for_user(
+            &Token::new("test_key", "test_secret"),
+            "123.456",
+            &user_data,
+        )
+        .unwrap();
+        assert_eq!(
+            result.user_data,
+            r#"{"id":"123","user_info":{"name":"Alice"}}"#
+        );
+        assert_eq!(result.parse_user_data::<UserInfo>().unwrap(), user_data);
+
+        let parsed = UserData::<Value>::parse(r#"{"id":"1","watchlist":["2"]}"#).unwrap();
+        assert_eq!(parsed.watchlist, vec!["2".to_string()]);
+        assert!(UserData::<Value>::parse("{}").is_err());
+    }
+
     #[cfg(feature = "encryption")]
     #[test]
     fn test_encrypted_channel_auth_with_encryption() {
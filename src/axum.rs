@@ -0,0 +1,189 @@
+//! Drop-in axum router for channel authorization and user authentication
+//!
+//! The router mounts `POST /pusher/auth` and `POST /pusher/user-auth`. Each request is
+//! parsed (form-encoded or JSON), passed to the user-supplied authorizer together with the
+//! request headers, and signed only if the authorizer allows it.
+
+use crate::auth::AuthRequest;
+use crate::json::Value;
+use crate::{Pusher, PusherError};
+use ::axum::Router;
+use ::axum::body::Bytes;
+use ::axum::http::{HeaderMap, StatusCode, header};
+use ::axum::response::{IntoResponse, Json, Response};
+use ::axum::routing::post;
+use std::future::Future;
+
+/// What the client is asking to be authorized for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuthTarget {
+    /// Subscription to a private, presence, or encrypted channel
+    Channel {
+        socket_id: String,
+        channel_name: String,
+    },
+    /// User authentication (signin)
+    User { socket_id: String },
+}
+
+/// Outcome of an authorizer callback
+#[derive(Debug, Clone)]
+pub enum AuthDecision {
+    /// Allow the request, with presence channel data or user data where required
+    Allow(Option<Value>),
+    /// Reject the request with `403 Forbidden`
+    Deny,
+}
+
+/// Builds a router serving `/pusher/auth` and `/pusher/user-auth`
+///
+/// User authentication requires the authorizer to return user data containing an `id`.
+pub fn pusher_auth_router<S, A, Fut>(pusher: Pusher, authorizer: A) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+    A: Fn(AuthTarget, HeaderMap) -> Fut + Clone + Send + Sync + 'static,
+    Fut: Future<Output = AuthDecision> + Send + 'static,
+{
+    let channel_pusher = pusher.clone();
+    let channel_authorizer = authorizer.clone();
+
+    Router::new()
+        .route(
+            "/pusher/auth",
+            post(move |headers: HeaderMap, body: Bytes| {
+                channel_auth(channel_pusher, channel_authorizer, headers, body)
+            }),
+        )
+        .route(
+            "/pusher/user-auth",
+            post(move |headers: HeaderMap, body: Bytes| {
+                user_auth(pusher, authorizer, headers, body)
+            }),
+        )
+}
+
+async fn channel_auth<A, Fut>(
+    pusher: Pusher,
+    authorizer: A,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response
+where
+    A: Fn(AuthTarget, HeaderMap) -> Fut,
+    Fut: Future<Output = AuthDecision>,
+{
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok());
+    let request = match AuthRequest::parse(content_type, &body) {
+        Ok(request) => request,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, e),
+    };
+
+    let target = AuthTarget::Channel {
+        socket_id: request.socket_id.clone(),
+        channel_name: request.channel_name.clone(),
+    };
+    match authorizer(target, headers).await {
+        AuthDecision::Allow(data) => match pusher.authorize_request(&request, data.as_ref()) {
+            Ok(auth) => Json(auth).into_response(),
+            Err(e) => error_response(StatusCode::FORBIDDEN, e),
+        },
+        AuthDecision::Deny => StatusCode::FORBIDDEN.into_response(),
+    }
+}
+
+async fn user_auth<A, Fut>(
+    pusher: Pusher,
+    authorizer: A,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response
+where
+    A: Fn(AuthTarget, HeaderMap) -> Fut,
+    Fut: Future<Output = AuthDecision>,
+{
+    let socket_id = url::form_urlencoded::parse(&body)
+        .find(|(key, _)| key == "socket_id")
+        .map(|(_, value)| value.into_owned());
+    let Some(socket_id) = socket_id else {
+        return (StatusCode::BAD_REQUEST, "Missing socket_id in auth request").into_response();
+    };
+
+    let target = AuthTarget::User {
+        socket_id: socket_id.clone(),
+    };
+    match authorizer(target, headers).await {
+        AuthDecision::Allow(Some(user_data)) => {
+            match pusher.authenticate_user(&socket_id, &user_data) {
+                Ok(auth) => Json(auth).into_response(),
+                Err(e) => error_response(StatusCode::FORBIDDEN, e),
+            }
+        }
+        AuthDecision::Allow(None) | AuthDecision::Deny => StatusCode::FORBIDDEN.into_response(),
+    }
+}
+
+fn error_response(status: StatusCode, error: PusherError) -> Response {
+    (status, error.to_string()).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+    use ::axum::body::Body;
+    use ::axum::http::Request;
+    use tower::ServiceExt;
+
+    fn router() -> Router {
+        let config = Config::builder()
+            .app_id("123")
+            .key("test_key")
+            .secret("test_secret")
+            .build()
+            .unwrap();
+        let pusher = Pusher::new(config).unwrap();
+
+        pusher_auth_router(pusher, |target, _headers| async move {
+            match target {
+                AuthTarget::Channel { channel_name, .. } if channel_name == "private-allowed" => {
+                    AuthDecision::Allow(None)
+                }
+                _ => AuthDecision::Deny,
+            }
+        })
+    }
+
+    fn auth_request(body: &'static str) -> Request<Body> {
+        Request::post("/pusher/auth")
+            .header("content-type", "application/x-www-form-urlencoded")
+            .body(Body::from(body))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_channel_auth_router() {
+        let response = router()
+            .oneshot(auth_request(
+                "socket_id=123.456&channel_name=private-allowed",
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = router()
+            .oneshot(auth_request(
+                "socket_id=123.456&channel_name=private-denied",
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let response = router()
+            .oneshot(auth_request("channel_name=private-allowed"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
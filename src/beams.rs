@@ -0,0 +1,315 @@
+//! Pusher Beams push notifications
+//!
+//! Publishes through the same transport, interceptors, retry policy, and clock as the
+//! [`Pusher`] client it is created from.
+
+use crate::json::{self, Value};
+use crate::{Pusher, PusherError, Result};
+use base64::Engine as _;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+use zeroize::Zeroizing;
+
+/// Maximum number of interests per publish
+pub const MAX_INTERESTS: usize = 100;
+/// Maximum number of users per publish
+pub const MAX_USERS: usize = 1000;
+/// Maximum length of an interest name or user ID
+pub const MAX_NAME_LENGTH: usize = 164;
+/// Lifetime of tokens created by [`Beams::generate_token`]
+pub const TOKEN_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Response to a successful publish
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct PublishResponse {
+    #[serde(rename = "publishId")]
+    pub publish_id: String,
+}
+
+/// Beams auth token to return to a client SDK
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BeamsToken {
+    pub token: String,
+}
+
+#[derive(Serialize)]
+struct PublishBody<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    interests: Option<&'a [String]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    users: Option<&'a [String]>,
+    #[serde(flatten)]
+    request: &'a Value,
+}
+
+#[derive(Serialize)]
+struct Claims<'a> {
+    sub: &'a str,
+    exp: u64,
+    iss: String,
+}
+
+/// Beams client for one instance
+#[derive(Clone)]
+pub struct Beams {
+    pusher: Pusher,
+    instance_id: String,
+    secret_key: Arc<Zeroizing<String>>,
+    base_url: String,
+}
+
+impl Beams {
+    /// Creates a Beams client that sends requests through `pusher`
+    pub fn new(
+        pusher: &Pusher,
+        instance_id: impl Into<String>,
+        secret_key: impl Into<String>,
+    ) -> Result<Self> {
+        let instance_id = instance_id.into();
+        let secret_key = Zeroizing::new(secret_key.into());
+        if instance_id.is_empty() {
+            return Err(PusherError::Config {
+                message: "Beams instance ID cannot be empty".to_string(),
+            });
+        }
+        if secret_key.is_empty() {
+            return Err(PusherError::Config {
+                message: "Beams secret key cannot be empty".to_string(),
+            });
+        }
+
+        Ok(Self {
+            pusher: pusher.clone(),
+            base_url: format!("https://{}.pushnotifications.pusher.com", instance_id),
+            instance_id,
+            secret_key: Arc::new(secret_key),
+        })
+    }
+
+    /// Overrides the API base URL, e.g. for a local test server
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn instance_id(&self) -> &str {
+        &self.instance_id
+    }
+
+    /// Publishes a notification to devices subscribed to any of the interests
+    ///
+    /// `request` holds the platform payloads, e.g. `{"apns": {...}, "fcm": {...}}`.
+    pub async fn publish_to_interests(
+        &self,
+        interests: &[String],
+        request: &Value,
+    ) -> Result<PublishResponse> {
+        validate_names("interest", interests, MAX_INTERESTS, true)?;
+        let body = PublishBody {
+            interests: Some(interests),
+            users: None,
+            request,
+        };
+        self.publish("interests", &body).await
+    }
+
+    /// Publishes a notification to the devices of authenticated users
+    pub async fn publish_to_users(
+        &self,
+        users: &[String],
+        request: &Value,
+    ) -> Result<PublishResponse> {
+        validate_names("user ID", users, MAX_USERS, false)?;
+        let body = PublishBody {
+            interests: None,
+            users: Some(users),
+            request,
+        };
+        self.publish("users", &body).await
+    }
+
+    /// Deletes a user and all their devices
+    pub async fn delete_user(&self, user_id: &str) -> Result<()> {
+        validate_names("user ID", &[user_id.to_string()], 1, false)?;
+        let mut url = url::Url::parse(&self.base_url).map_err(|e| PusherError::Config {
+            message: format!("Invalid Beams base URL: {}", e),
+        })?;
+        url.path_segments_mut()
+            .map_err(|_| PusherError::Config {
+                message: "Invalid Beams base URL".to_string(),
+            })?
+            .extend([
+                "customer_api",
+                "v1",
+                "instances",
+                &self.instance_id,
+                "users",
+                user_id,
+            ]);
+        self.pusher
+            .send_external("DELETE", url.as_str(), None, &self.auth_headers())
+            .await?;
+        Ok(())
+    }
+
+    /// Generates the auth token that authenticates `user_id` on a client device
+    pub fn generate_token(&self, user_id: &str) -> Result<BeamsToken> {
+        validate_names("user ID", &[user_id.to_string()], 1, false)?;
+
+        let header = URL_SAFE_NO_PAD.encode(r#"{"alg":"HS256","typ":"JWT"}"#);
+        let claims = Claims {
+            sub: user_id,
+            exp: self.pusher.config().clock_handle().unix_secs() + TOKEN_TTL.as_secs(),
+            iss: format!("https://{}.pushnotifications.pusher.com", self.instance_id),
+        };
+        let claims = URL_SAFE_NO_PAD.encode(json::to_string(&claims)?);
+        let signing_input = format!("{}.{}", header, claims);
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.secret_key.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(signing_input.as_bytes());
+        let signature = URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes());
+
+        Ok(BeamsToken {
+            token: format!("{}.{}", signing_input, signature),
+        })
+    }
+
+    async fn publish(&self, target: &str, body: &PublishBody<'_>) -> Result<PublishResponse> {
+        let body = json::to_string(body).map_err(|e| PusherError::Validation {
+            message: format!("Beams publish request must be a JSON object: {}", e),
+        })?;
+        let url = format!(
+            "{}/publish_api/v1/instances/{}/publishes/{}",
+            self.base_url, self.instance_id, target
+        );
+        let response = self
+            .pusher
+            .send_external("POST", &url, Some(&body), &self.auth_headers())
+            .await?;
+        response.json()
+    }
+
+    fn auth_headers(&self) -> Vec<(&'static str, String)> {
+        vec![(
+            "Authorization",
+            format!("Bearer {}", self.secret_key.as_str()),
+        )]
+    }
+}
+
+impl fmt::Debug for Beams {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Beams")
+            .field("instance_id", &self.instance_id)
+            .field("base_url", &self.base_url)
+            .finish()
+    }
+}
+
+fn validate_names(kind: &str, names: &[String], max: usize, check_charset: bool) -> Result<()> {
+    if names.is_empty() {
+        return Err(PusherError::Validation {
+            message: format!("Must specify at least one {}", kind),
+        });
+    }
+    if names.len() > max {
+        return Err(PusherError::Validation {
+            message: format!(
+                "Can't publish to more than {} {}s (got {})",
+                max,
+                kind,
+                names.len()
+            ),
+        });
+    }
+
+    for name in names {
+        if name.is_empty() || name.len() > MAX_NAME_LENGTH {
+            return Err(PusherError::Validation {
+                message: format!(
+                    "Invalid {} '{}': must be 1-{} characters",
+                    kind, name, MAX_NAME_LENGTH
+                ),
+            });
+        }
+        let valid_chars = name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"_-=@,.;".contains(&b));
+        if check_charset && !valid_chars {
+            return Err(PusherError::Validation {
+                message: format!(
+                    "Invalid {} '{}': must match [A-Za-z0-9_\\-=@,.;]+",
+                    kind, name
+                ),
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+    use crate::clock::FixedClock;
+
+    fn beams() -> Beams {
+        let config = Config::builder()
+            .app_id("123")
+            .key("key")
+            .secret("secret")
+            .clock(FixedClock::from_unix_secs(1_700_000_000))
+            .build()
+            .unwrap();
+        Beams::new(&Pusher::new(config).unwrap(), "instance", "beams-secret").unwrap()
+    }
+
+    #[test]
+    fn test_generate_token() {
+        let token = beams().generate_token("user-1").unwrap().token;
+        let parts: Vec<&str> = token.split('.').collect();
+        assert_eq!(parts.len(), 3);
+
+        let claims: Value = json::from_slice(&URL_SAFE_NO_PAD.decode(parts[1]).unwrap()).unwrap();
+        assert_eq!(
+            claims,
+            json::json!({
+                "sub": "user-1",
+                "exp": 1_700_000_000u64 + 86_400,
+                "iss": "https://instance.pushnotifications.pusher.com",
+            })
+        );
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(b"beams-secret").unwrap();
+        mac.update(format!("{}.{}", parts[0], parts[1]).as_bytes());
+        mac.verify_slice(&URL_SAFE_NO_PAD.decode(parts[2]).unwrap())
+            .unwrap();
+    }
+
+    #[test]
+    fn test_validate_names() {
+        let interests = vec!["donuts".to_string()];
+        assert!(validate_names("interest", &interests, MAX_INTERESTS, true).is_ok());
+        assert!(validate_names("interest", &[], MAX_INTERESTS, true).is_err());
+        assert!(validate_names("interest", &["a b".to_string()], MAX_INTERESTS, true).is_err());
+        assert!(validate_names("user ID", &["a b".to_string()], MAX_USERS, false).is_ok());
+
+        let too_many: Vec<String> = (0..=MAX_INTERESTS).map(|i| i.to_string()).collect();
+        assert!(validate_names("interest", &too_many, MAX_INTERESTS, true).is_err());
+        assert!(validate_names("user ID", &["u".repeat(165)], MAX_USERS, false).is_err());
+        assert!(beams().generate_token("").is_err());
+    }
+
+    #[test]
+    fn test_new_requires_credentials() {
+        let pusher = Pusher::new(Config::new("123", "key", "secret")).unwrap();
+        assert!(Beams::new(&pusher, "", "secret").is_err());
+        assert!(Beams::new(&pusher, "instance", "").is_err());
+    }
+}
@@ -0,0 +1,264 @@
+//! Blocking client for non-async applications
+//!
+//! [`Pusher`] drives the async client on a small private runtime, the same way
+//! `reqwest::blocking` does, so signing, retries, interceptors and encryption behave
+//! exactly as in the async API. It must not be used from within an async runtime.
+
+use crate::channel::{EncryptedChannel, PresenceChannel};
+use crate::events::{BatchEvent, EventData, TriggerParams};
+use crate::json::Value;
+use crate::{Channel, Config, PusherError, Result, Webhook, auth};
+use std::borrow::Borrow;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+
+pub use crate::transport::Response;
+
+/// Blocking Pusher client
+#[derive(Clone)]
+pub struct Pusher {
+    inner: crate::Pusher,
+    runtime: Arc<Runtime>,
+}
+
+impl Pusher {
+    /// Creates a new blocking client
+    pub fn new(config: Config) -> Result<Self> {
+        Self::from_async(crate::Pusher::new(config)?)
+    }
+
+    /// Creates a new blocking client from a Pusher URL
+    pub fn from_url(url: &str, additional_config: Option<Config>) -> Result<Self> {
+        Self::from_async(crate::Pusher::from_url(url, additional_config)?)
+    }
+
+    /// Wraps an existing async client
+    pub fn from_async(inner: crate::Pusher) -> Result<Self> {
+        // A single worker keeps connection tasks running while any number of threads
+        // (e.g. a Rayon pool) block on requests concurrently
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("pushers-blocking")
+            .enable_all()
+            .build()
+            .map_err(|e| PusherError::Config {
+                message: format!("Failed to start blocking runtime: {}", e),
+            })?;
+        Ok(Self {
+            inner,
+            runtime: Arc::new(runtime),
+        })
+    }
+
+    /// Gets the underlying async client
+    pub fn as_async(&self) -> &crate::Pusher {
+        &self.inner
+    }
+
+    /// Gets the configuration
+    pub fn config(&self) -> &Config {
+        self.inner.config()
+    }
+
+    fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+
+    /// Triggers an event on channels
+    pub fn trigger<D: Into<EventData>>(
+        &self,
+        channels: impl IntoIterator<Item = impl Borrow<Channel>>,
+        event: &str,
+        data: D,
+        params: Option<TriggerParams>,
+    ) -> Result<Response> {
+        self.block_on(self.inner.trigger(channels, event, data, params))
+    }
+
+    /// Triggers an event on channel names (convenience method)
+    pub fn trigger_on_channels<D: Into<EventData>>(
+        &self,
+        channel_names: &[String],
+        event: &str,
+        data: D,
+        params: Option<TriggerParams>,
+    ) -> Result<Response> {
+        self.block_on(
+            self.inner
+                .trigger_on_channels(channel_names, event, data, params),
+        )
+    }
+
+    /// Triggers an event on a single encrypted channel
+    pub fn trigger_encrypted<D: Into<EventData>>(
+        &self,
+        channel: &EncryptedChannel,
+        event: &str,
+        data: D,
+        params: Option<TriggerParams>,
+    ) -> Result<Response> {
+        self.block_on(self.inner.trigger_encrypted(channel, event, data, params))
+    }
+
+    /// Triggers a batch of events
+    pub fn trigger_batch(&self, batch: Vec<BatchEvent>) -> Result<Response> {
+        self.block_on(self.inner.trigger_batch(batch))
+    }
+
+    /// Sends an event to a user
+    pub fn send_to_user<D: Into<EventData>>(
+        &self,
+        user_id: &str,
+        event: &str,
+        data: D,
+    ) -> Result<Response> {
+        self.block_on(self.inner.send_to_user(user_id, event, data))
+    }
+
+    /// Terminates user connections
+    pub fn terminate_user_connections(&self, user_id: &str) -> Result<Response> {
+        self.block_on(self.inner.terminate_user_connections(user_id))
+    }
+
+    /// Makes a GET request, e.g. to query `/channels`
+    pub fn get(&self, path: &str, params: Option<&BTreeMap<String, String>>) -> Result<Response> {
+        self.block_on(self.inner.get(path, params))
+    }
+
+    /// Makes a POST request
+    pub fn post(&self, path: &str, body: &Value) -> Result<Response> {
+        self.block_on(self.inner.post(path, body))
+    }
+
+    /// Authorizes a channel, signing with the configured signer
+    pub fn authorize_channel(
+        &self,
+        socket_id: &str,
+        channel: &Channel,
+        data: Option<&Value>,
+    ) -> Result<auth::SocketAuth> {
+        self.block_on(self.inner.authorize_channel_async(socket_id, channel, data))
+    }
+
+    /// Authorizes a presence channel, signing with the configured signer
+    pub fn authorize_presence<T: serde::Serialize + Sync>(
+        &self,
+        socket_id: &str,
+        channel: &PresenceChannel,
+        member: &auth::PresenceMember<T>,
+    ) -> Result<auth::SocketAuth> {
+        self.block_on(
+            self.inner
+                .authorize_presence_async(socket_id, channel, member),
+        )
+    }
+
+    /// Authorizes several channels for one socket, signing with the configured signer
+    pub fn authorize_channels(
+        &self,
+        socket_id: &str,
+        channels: &[Channel],
+        data_per_channel: &HashMap<String, Value>,
+    ) -> Result<BTreeMap<String, auth::SocketAuth>> {
+        self.block_on(
+            self.inner
+                .authorize_channels_async(socket_id, channels, data_per_channel),
+        )
+    }
+
+    /// Authenticates a user, signing with the configured signer
+    pub fn authenticate_user(&self, socket_id: &str, user_data: &Value) -> Result<auth::UserAuth> {
+        self.block_on(self.inner.authenticate_user_async(socket_id, user_data))
+    }
+
+    /// Authenticates a user with typed `user_data`, signing with the configured signer
+    pub fn authenticate_user_typed<T: serde::Serialize + Sync>(
+        &self,
+        socket_id: &str,
+        user_data: &auth::UserData<T>,
+    ) -> Result<auth::UserAuth> {
+        self.block_on(
+            self.inner
+                .authenticate_user_typed_async(socket_id, user_data),
+        )
+    }
+
+    /// Creates a webhook from request data
+    pub fn webhook(&self, headers: &BTreeMap<String, String>, body: &str) -> Webhook {
+        self.inner.webhook(headers, body)
+    }
+}
+
+impl fmt::Debug for Pusher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("blocking::Pusher")
+            .field("config", self.inner.config())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blocking_authorize() {
+        let pusher = Pusher::new(Config::new("123", "key", "secret")).unwrap();
+        let channel = Channel::from_string("private-orders").unwrap();
+
+        let blocking = pusher.authorize_channel("123.456", &channel, None).unwrap();
+        let sync = pusher
+            .as_async()
+            .authorize_channel("123.456", &channel, None)
+            .unwrap();
+        assert_eq!(blocking.auth, sync.auth);
+    }
+
+    #[test]
+    fn test_blocking_authorize_with_signer_only() {
+        let token = crate::Token::new("key", "secret");
+        let config = Config::builder()
+            .app_id("123")
+            .key("key")
+            .signer(token.clone())
+            .build()
+            .unwrap();
+        let pusher = Pusher::new(config).unwrap();
+
+        let presence = PresenceChannel::new("lobby").unwrap();
+        let member = auth::PresenceMember::new("1");
+        let auth = pusher
+            .authorize_presence("123.456", &presence, &member)
+            .unwrap();
+        let channel_data = auth.channel_data.unwrap();
+        assert_eq!(
+            auth.auth,
+            format!(
+                "key:{}",
+                token.sign(&format!("123.456:presence-lobby:{}", channel_data))
+            )
+        );
+
+        let channels = [Channel::from_string("private-orders").unwrap()];
+        let auths = pusher
+            .authorize_channels("123.456", &channels, &HashMap::new())
+            .unwrap();
+        assert_eq!(
+            auths["private-orders"].auth,
+            format!("key:{}", token.sign("123.456:private-orders"))
+        );
+    }
+
+    #[test]
+    fn test_blocking_validation_error() {
+        let pusher = Pusher::new(Config::new("123", "key", "secret")).unwrap();
+        let channels: Vec<Channel> = Vec::new();
+        assert!(matches!(
+            pusher.trigger(&channels, "event", "data", None),
+            Err(PusherError::Validation { .. })
+        ));
+    }
+}
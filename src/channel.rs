@@ -1,4 +1,6 @@
 use crate::{PusherError, Result};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+use std::collections::BTreeMap;
 use std::fmt;
 use std::str::FromStr;
 
@@ -9,6 +11,9 @@ pub enum Channel {
     Private(PrivateChannel),
     Presence(PresenceChannel),
     Encrypted(EncryptedChannel),
+    Cache(CacheChannel),
+    PrivateCache(PrivateCacheChannel),
+    PresenceCache(PresenceCacheChannel),
 }
 
 /// Public channel type
@@ -27,6 +32,18 @@ pub struct PresenceChannel(ChannelName);
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct EncryptedChannel(ChannelName);
 
+/// Public cache channel type
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CacheChannel(ChannelName);
+
+/// Private cache channel type
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PrivateCacheChannel(ChannelName);
+
+/// Presence cache channel type
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PresenceCacheChannel(ChannelName);
+
 /// Validated channel name
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ChannelName(String);
@@ -62,6 +79,19 @@ impl fmt::Display for ChannelName {
     }
 }
 
+impl Serialize for ChannelName {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for ChannelName {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        ChannelName::new(name).map_err(de::Error::custom)
+    }
+}
+
 /// Channel type enumeration
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ChannelType {
@@ -69,6 +99,9 @@ pub enum ChannelType {
     Private,
     Presence,
     Encrypted,
+    Cache,
+    PrivateCache,
+    PresenceCache,
 }
 
 impl Channel {
@@ -81,6 +114,16 @@ impl Channel {
             Ok(Channel::Encrypted(EncryptedChannel(ChannelName::new(
                 name,
             )?)))
+        } else if let Some(name) = s.strip_prefix("private-cache-") {
+            Ok(Channel::PrivateCache(PrivateCacheChannel(
+                ChannelName::new(name)?,
+            )))
+        } else if let Some(name) = s.strip_prefix("presence-cache-") {
+            Ok(Channel::PresenceCache(PresenceCacheChannel(
+                ChannelName::new(name)?,
+            )))
+        } else if let Some(name) = s.strip_prefix("cache-") {
+            Ok(Channel::Cache(CacheChannel(ChannelName::new(name)?)))
         } else if s.starts_with("presence-") {
             let name = s.strip_prefix("presence-").unwrap();
             Ok(Channel::Presence(PresenceChannel(ChannelName::new(name)?)))
@@ -92,13 +135,62 @@ impl Channel {
         }
     }
 
-    /// Gets the full channel name including prefix
+    /// Parses a comma-separated channel list such as `"a,private-b, presence-c"`
+    ///
+    /// Whitespace around items is ignored and an empty input yields no channels. Since `,`
+    /// is also a valid channel name character, names containing commas can't be listed.
+    pub fn parse_list(list: &str) -> Result<Vec<Self>> {
+        if list.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+
+        list.split(',')
+            .enumerate()
+            .map(|(index, item)| {
+                let name = item.trim();
+                Channel::from_string(name).map_err(|e| {
+                    let reason = match e {
+                        PusherError::Validation { message } => message,
+                        other => other.to_string(),
+                    };
+                    PusherError::Validation {
+                        message: format!(
+                            "Invalid channel at index {} ('{}'): {}",
+                            index, name, reason
+                        ),
+                    }
+                })
+            })
+            .collect()
+    }
+
+    /// Gets the full channel name including prefix, as it appears on the wire
+    ///
+    /// Parsing only strips an exact type prefix, so for a channel from
+    /// [`Channel::from_string`] this is byte-for-byte the parsed string and can be compared
+    /// directly against channel names received in webhooks.
     pub fn full_name(&self) -> String {
         match self {
             Channel::Public(ch) => ch.0.to_string(),
             Channel::Private(ch) => format!("private-{}", ch.0),
             Channel::Presence(ch) => format!("presence-{}", ch.0),
             Channel::Encrypted(ch) => format!("private-encrypted-{}", ch.0),
+            Channel::Cache(ch) => format!("cache-{}", ch.0),
+            Channel::PrivateCache(ch) => format!("private-cache-{}", ch.0),
+            Channel::PresenceCache(ch) => format!("presence-cache-{}", ch.0),
+        }
+    }
+
+    /// Gets the channel name without its type prefix, e.g. `orders` for `private-orders`
+    pub fn base_name(&self) -> &str {
+        match self {
+            Channel::Public(ch) => ch.0.as_str(),
+            Channel::Private(ch) => ch.0.as_str(),
+            Channel::Presence(ch) => ch.0.as_str(),
+            Channel::Encrypted(ch) => ch.0.as_str(),
+            Channel::Cache(ch) => ch.0.as_str(),
+            Channel::PrivateCache(ch) => ch.0.as_str(),
+            Channel::PresenceCache(ch) => ch.0.as_str(),
         }
     }
 
@@ -109,18 +201,166 @@ impl Channel {
             Channel::Private(_) => ChannelType::Private,
             Channel::Presence(_) => ChannelType::Presence,
             Channel::Encrypted(_) => ChannelType::Encrypted,
+            Channel::Cache(_) => ChannelType::Cache,
+            Channel::PrivateCache(_) => ChannelType::PrivateCache,
+            Channel::PresenceCache(_) => ChannelType::PresenceCache,
         }
     }
 
     /// Checks if the channel requires authentication
     pub fn requires_auth(&self) -> bool {
-        !matches!(self, Channel::Public(_))
+        !matches!(self, Channel::Public(_) | Channel::Cache(_))
+    }
+
+    /// Checks if the channel is a cache channel
+    pub fn is_cache(&self) -> bool {
+        matches!(
+            self,
+            Channel::Cache(_) | Channel::PrivateCache(_) | Channel::PresenceCache(_)
+        )
     }
 
     /// Checks if the channel is encrypted
     pub fn is_encrypted(&self) -> bool {
         matches!(self, Channel::Encrypted(_))
     }
+
+    /// Creates a channel of the given type from an already validated name
+    pub fn with_name(channel_type: ChannelType, name: ChannelName) -> Self {
+        match channel_type {
+            ChannelType::Public => Channel::Public(PublicChannel(name)),
+            ChannelType::Private => Channel::Private(PrivateChannel(name)),
+            ChannelType::Presence => Channel::Presence(PresenceChannel(name)),
+            ChannelType::Encrypted => Channel::Encrypted(EncryptedChannel(name)),
+            ChannelType::Cache => Channel::Cache(CacheChannel(name)),
+            ChannelType::PrivateCache => Channel::PrivateCache(PrivateCacheChannel(name)),
+            ChannelType::PresenceCache => Channel::PresenceCache(PresenceCacheChannel(name)),
+        }
+    }
+
+    /// Starts a hierarchical public channel name, e.g. `orders-{tenant}-{order}`
+    pub fn public(root: impl fmt::Display) -> ChannelBuilder {
+        ChannelBuilder::new(ChannelType::Public, root)
+    }
+
+    /// Starts a hierarchical private channel name
+    pub fn private(root: impl fmt::Display) -> ChannelBuilder {
+        ChannelBuilder::new(ChannelType::Private, root)
+    }
+
+    /// Starts a hierarchical presence channel name
+    pub fn presence(root: impl fmt::Display) -> ChannelBuilder {
+        ChannelBuilder::new(ChannelType::Presence, root)
+    }
+
+    /// Starts a hierarchical encrypted channel name
+    pub fn encrypted(root: impl fmt::Display) -> ChannelBuilder {
+        ChannelBuilder::new(ChannelType::Encrypted, root)
+    }
+
+    /// Starts a hierarchical public cache channel name
+    pub fn cache(root: impl fmt::Display) -> ChannelBuilder {
+        ChannelBuilder::new(ChannelType::Cache, root)
+    }
+
+    /// Starts a hierarchical private cache channel name
+    pub fn private_cache(root: impl fmt::Display) -> ChannelBuilder {
+        ChannelBuilder::new(ChannelType::PrivateCache, root)
+    }
+
+    /// Starts a hierarchical presence cache channel name
+    pub fn presence_cache(root: impl fmt::Display) -> ChannelBuilder {
+        ChannelBuilder::new(ChannelType::PresenceCache, root)
+    }
+}
+
+/// Default separator placed between channel name segments
+pub const DEFAULT_SEGMENT_SEPARATOR: &str = "-";
+
+/// Builder for channel names made of several segments
+///
+/// ```
+/// # use pushers::Channel;
+/// let channel = Channel::private("orders").segment(42).segment("abc").build()?;
+/// assert_eq!(channel.full_name(), "private-orders-42-abc");
+/// # Ok::<(), pushers::PusherError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct ChannelBuilder {
+    channel_type: ChannelType,
+    segments: Vec<String>,
+    separator: String,
+}
+
+impl ChannelBuilder {
+    /// Creates a builder for a channel of the given type, starting at `root`
+    pub fn new(channel_type: ChannelType, root: impl fmt::Display) -> Self {
+        Self {
+            channel_type,
+            segments: vec![root.to_string()],
+            separator: DEFAULT_SEGMENT_SEPARATOR.to_string(),
+        }
+    }
+
+    /// Appends a segment
+    pub fn segment(mut self, segment: impl fmt::Display) -> Self {
+        self.segments.push(segment.to_string());
+        self
+    }
+
+    /// Sets the separator placed between segments (default `-`)
+    pub fn separator(mut self, separator: impl Into<String>) -> Self {
+        self.separator = separator.into();
+        self
+    }
+
+    /// Validates each segment and builds the channel
+    ///
+    /// Segments must be non-empty, use only valid channel name characters and must not
+    /// contain the separator, so the name can be split back into its segments. The name must
+    /// not start with another type's prefix, e.g. a public `private-...` or a private
+    /// `encrypted-...`, since it would be read back as that type.
+    pub fn build(self) -> Result<Channel> {
+        if self.separator.is_empty() || !CHANNEL_NAME_PATTERN.is_match(&self.separator) {
+            return Err(PusherError::Validation {
+                message: format!("Invalid channel segment separator: '{}'", self.separator),
+            });
+        }
+
+        for segment in &self.segments {
+            if segment.is_empty() {
+                return Err(PusherError::Validation {
+                    message: "Channel name segment cannot be empty".to_string(),
+                });
+            }
+            if !CHANNEL_NAME_PATTERN.is_match(segment) {
+                return Err(PusherError::Validation {
+                    message: format!("Invalid channel name segment: '{}'", segment),
+                });
+            }
+            if segment.contains(self.separator.as_str()) {
+                return Err(PusherError::Validation {
+                    message: format!(
+                        "Channel name segment '{}' contains the separator '{}'",
+                        segment, self.separator
+                    ),
+                });
+            }
+        }
+
+        let name = ChannelName::new(self.segments.join(&self.separator))?;
+        let channel = Channel::with_name(self.channel_type, name);
+        let full_name = channel.full_name();
+        if Channel::from_string(full_name.as_str())?.channel_type() != self.channel_type {
+            return Err(PusherError::Validation {
+                message: format!(
+                    "Channel name '{}' starts with a reserved channel prefix",
+                    full_name
+                ),
+            });
+        }
+        Ok(channel)
+    }
 }
 
 impl fmt::Display for Channel {
@@ -137,6 +377,42 @@ impl FromStr for Channel {
     }
 }
 
+/// Serialized as the full wire name, e.g. `"private-orders"`
+impl Serialize for Channel {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.full_name())
+    }
+}
+
+impl<'de> Deserialize<'de> for Channel {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Channel::from_string(name).map_err(de::Error::custom)
+    }
+}
+
+macro_rules! impl_from_channel_type {
+    ($($ty:ident => $variant:ident),* $(,)?) => {
+        $(
+            impl From<$ty> for Channel {
+                fn from(channel: $ty) -> Self {
+                    Channel::$variant(channel)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_channel_type! {
+    PublicChannel => Public,
+    PrivateChannel => Private,
+    PresenceChannel => Presence,
+    EncryptedChannel => Encrypted,
+    CacheChannel => Cache,
+    PrivateCacheChannel => PrivateCache,
+    PresenceCacheChannel => PresenceCache,
+}
+
 // Implement convenience constructors for specific channel types
 impl PublicChannel {
     pub fn new(name: impl Into<String>) -> Result<Self> {
@@ -162,6 +438,184 @@ impl EncryptedChannel {
     }
 }
 
+impl CacheChannel {
+    pub fn new(name: impl Into<String>) -> Result<Self> {
+        Ok(Self(ChannelName::new(name)?))
+    }
+}
+
+impl PrivateCacheChannel {
+    pub fn new(name: impl Into<String>) -> Result<Self> {
+        Ok(Self(ChannelName::new(name)?))
+    }
+}
+
+impl PresenceCacheChannel {
+    pub fn new(name: impl Into<String>) -> Result<Self> {
+        Ok(Self(ChannelName::new(name)?))
+    }
+}
+
+/// Matches channel names by exact name or prefix
+///
+/// The same pattern can produce the `filter_by_prefix` parameter for the channels list
+/// endpoint and filter channels received in webhooks.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ChannelPattern {
+    /// Matches a single full channel name
+    Exact(String),
+    /// Matches every full channel name starting with the prefix
+    Prefix(String),
+}
+
+impl ChannelPattern {
+    /// Matches exactly one channel name, e.g. `presence-lobby`
+    pub fn exact(name: impl Into<String>) -> Self {
+        ChannelPattern::Exact(name.into())
+    }
+
+    /// Matches channel names starting with `prefix`, e.g. `presence-game-`
+    pub fn prefix(prefix: impl Into<String>) -> Self {
+        ChannelPattern::Prefix(prefix.into())
+    }
+
+    /// Checks a full channel name against the pattern
+    pub fn matches(&self, channel: &str) -> bool {
+        match self {
+            ChannelPattern::Exact(name) => channel == name,
+            ChannelPattern::Prefix(prefix) => channel.starts_with(prefix.as_str()),
+        }
+    }
+
+    /// Checks a channel against the pattern
+    pub fn matches_channel(&self, channel: &Channel) -> bool {
+        self.matches(&channel.full_name())
+    }
+
+    /// Value for the `filter_by_prefix` parameter of `GET /channels`
+    ///
+    /// Exact patterns use the name itself, which may return extra channels sharing
+    /// that prefix; filter the result with [`ChannelPattern::matches`].
+    pub fn filter_by_prefix(&self) -> &str {
+        match self {
+            ChannelPattern::Exact(name) => name,
+            ChannelPattern::Prefix(prefix) => prefix,
+        }
+    }
+
+    /// Query parameters for `GET /channels`
+    pub fn query_params(&self) -> BTreeMap<String, String> {
+        BTreeMap::from([(
+            "filter_by_prefix".to_string(),
+            self.filter_by_prefix().to_string(),
+        )])
+    }
+}
+
+impl fmt::Display for ChannelPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChannelPattern::Exact(name) => write!(f, "{}", name),
+            ChannelPattern::Prefix(prefix) => write!(f, "{}*", prefix),
+        }
+    }
+}
+
+/// Maximum number of channels a single event can be triggered on
+pub const MAX_TRIGGER_CHANNELS: usize = 100;
+
+/// Deduplicated, insertion-ordered set of channels for a single trigger
+///
+/// The 100-channel limit is enforced when channels are added, so a `ChannelSet`
+/// is always valid to trigger on (as long as it is non-empty).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChannelSet {
+    channels: Vec<Channel>,
+}
+
+impl ChannelSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a channel, returning `false` if it was already present
+    pub fn insert(&mut self, channel: Channel) -> Result<bool> {
+        if self.channels.contains(&channel) {
+            return Ok(false);
+        }
+        if self.channels.len() >= MAX_TRIGGER_CHANNELS {
+            return Err(PusherError::Validation {
+                message: format!(
+                    "Can't trigger to more than {} channels (adding '{}')",
+                    MAX_TRIGGER_CHANNELS, channel
+                ),
+            });
+        }
+        self.channels.push(channel);
+        Ok(true)
+    }
+
+    /// Parses and adds a channel by its full name
+    pub fn insert_str(&mut self, name: &str) -> Result<bool> {
+        self.insert(Channel::from_string(name)?)
+    }
+
+    /// Builds a set from channels, dropping duplicates
+    pub fn try_from_iter<I: IntoIterator<Item = Channel>>(channels: I) -> Result<Self> {
+        let mut set = Self::new();
+        for channel in channels {
+            set.insert(channel)?;
+        }
+        Ok(set)
+    }
+
+    pub fn contains(&self, channel: &Channel) -> bool {
+        self.channels.contains(channel)
+    }
+
+    pub fn len(&self) -> usize {
+        self.channels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.channels.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Channel> {
+        self.channels.iter()
+    }
+
+    pub fn as_slice(&self) -> &[Channel] {
+        &self.channels
+    }
+}
+
+impl TryFrom<Vec<Channel>> for ChannelSet {
+    type Error = PusherError;
+
+    fn try_from(channels: Vec<Channel>) -> Result<Self> {
+        Self::try_from_iter(channels)
+    }
+}
+
+impl IntoIterator for ChannelSet {
+    type Item = Channel;
+    type IntoIter = std::vec::IntoIter<Channel>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.channels.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a ChannelSet {
+    type Item = &'a Channel;
+    type IntoIter = std::slice::Iter<'a, Channel>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.channels.iter()
+    }
+}
+
 // Validation moved here from util.rs
 use regex::Regex;
 use std::sync::LazyLock;
@@ -194,6 +648,84 @@ fn validate_channel_name(name: &str) -> Result<()> {
     Ok(())
 }
 
+/// Channel type prefixes, in the order [`Channel::from_string`] checks them
+const CHANNEL_PREFIXES: [&str; 6] = [
+    "private-encrypted-",
+    "private-cache-",
+    "presence-cache-",
+    "cache-",
+    "presence-",
+    "private-",
+];
+
+const fn starts_with(s: &[u8], prefix: &[u8]) -> bool {
+    if s.len() < prefix.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < prefix.len() {
+        if s[i] != prefix[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Const equivalent of the validation done by [`Channel::from_string`], used by [`channel!`]
+///
+/// [`channel!`]: crate::channel!
+#[doc(hidden)]
+pub const fn is_valid_channel_name(name: &str) -> bool {
+    let bytes = name.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        let valid = b.is_ascii_alphanumeric()
+            || matches!(b, b'_' | b'-' | b'=' | b'@' | b',' | b'.' | b';');
+        if !valid {
+            return false;
+        }
+        i += 1;
+    }
+
+    let mut prefix_len = 0;
+    let mut p = 0;
+    while p < CHANNEL_PREFIXES.len() {
+        if starts_with(bytes, CHANNEL_PREFIXES[p].as_bytes()) {
+            prefix_len = CHANNEL_PREFIXES[p].len();
+            break;
+        }
+        p += 1;
+    }
+
+    let base_len = bytes.len() - prefix_len;
+    base_len > 0 && base_len <= 200
+}
+
+/// Creates a [`Channel`] from a string literal, validating the name at compile time
+///
+/// ```
+/// let lobby = pushers::channel!("presence-lobby");
+/// assert_eq!(lobby.channel_type(), pushers::ChannelType::Presence);
+/// ```
+///
+/// Invalid names fail to compile:
+///
+/// ```compile_fail
+/// let channel = pushers::channel!("bad name");
+/// ```
+#[macro_export]
+macro_rules! channel {
+    ($name:expr) => {{
+        const _: () = assert!(
+            $crate::channel::is_valid_channel_name($name),
+            "invalid channel name"
+        );
+        $crate::Channel::from_string($name).expect("channel name validated at compile time")
+    }};
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -215,6 +747,42 @@ mod tests {
         assert_eq!(private.channel_type(), ChannelType::Private);
     }
 
+    #[test]
+    fn test_cache_channels() {
+        let cases = [
+            ("cache-test", ChannelType::Cache, false),
+            ("private-cache-test", ChannelType::PrivateCache, true),
+            ("presence-cache-test", ChannelType::PresenceCache, true),
+        ];
+        for (name, channel_type, requires_auth) in cases {
+            let channel = Channel::from_string(name).unwrap();
+            assert_eq!(channel.channel_type(), channel_type);
+            assert_eq!(channel.requires_auth(), requires_auth);
+            assert!(channel.is_cache());
+            assert_eq!(channel.full_name(), name);
+        }
+        assert!(!Channel::from_string("private-test").unwrap().is_cache());
+    }
+
+    #[test]
+    fn test_channel_serde() {
+        let channel = Channel::from_string("presence-cache-room").unwrap();
+        let serialized = crate::json::to_string(&channel).unwrap();
+        assert_eq!(serialized, r#""presence-cache-room""#);
+        assert_eq!(
+            crate::json::from_str::<Channel>(&serialized).unwrap(),
+            channel
+        );
+        assert!(crate::json::from_str::<Channel>(r#""bad name""#).is_err());
+
+        let name = ChannelName::new("room").unwrap();
+        assert_eq!(crate::json::to_string(&name).unwrap(), r#""room""#);
+        assert_eq!(
+            crate::json::from_str::<ChannelName>(r#""room""#).unwrap(),
+            name
+        );
+    }
+
     #[test]
     fn test_channel_name_validation() {
         assert!(ChannelName::new("").is_err());
@@ -222,4 +790,193 @@ mod tests {
         assert!(ChannelName::new("test channel").is_err()); // space not allowed
         assert!(ChannelName::new("test-channel_123").is_ok());
     }
+
+    #[test]
+    fn test_channel_set_dedupes() {
+        let mut set = ChannelSet::new();
+        assert!(set.insert_str("orders").unwrap());
+        assert!(set.insert_str("private-orders").unwrap());
+        assert!(!set.insert_str("orders").unwrap());
+        assert_eq!(set.len(), 2);
+        let names: Vec<String> = set.iter().map(|c| c.full_name()).collect();
+        assert_eq!(names, vec!["orders", "private-orders"]);
+    }
+
+    #[test]
+    fn test_channel_set_limit() {
+        let mut set = ChannelSet::new();
+        for i in 0..MAX_TRIGGER_CHANNELS {
+            set.insert_str(&format!("channel-{}", i)).unwrap();
+        }
+        // Duplicates are still accepted once the set is full
+        assert!(!set.insert_str("channel-0").unwrap());
+        assert!(set.insert_str("channel-extra").is_err());
+
+        let channels: Vec<Channel> = (0..=MAX_TRIGGER_CHANNELS)
+            .map(|i| Channel::from_string(format!("c{}", i)).unwrap())
+            .collect();
+        assert!(ChannelSet::try_from(channels).is_err());
+    }
+
+    #[test]
+    fn test_channel_builder() {
+        let channel = Channel::private("orders")
+            .segment("tenant1")
+            .segment(42)
+            .build()
+            .unwrap();
+        assert_eq!(channel.full_name(), "private-orders-tenant1-42");
+        assert_eq!(channel.channel_type(), ChannelType::Private);
+
+        let channel = Channel::presence_cache("room")
+            .separator(".")
+            .segment("my-team")
+            .build()
+            .unwrap();
+        assert_eq!(channel.full_name(), "presence-cache-room.my-team");
+        assert_eq!(
+            channel,
+            Channel::from_string("presence-cache-room.my-team").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_channel_builder_validation() {
+        assert!(Channel::public("orders").segment("").build().is_err());
+        assert!(Channel::public("orders").segment("a b").build().is_err());
+        assert!(Channel::public("orders").segment("a-b").build().is_err());
+        assert!(Channel::public("orders").separator("").build().is_err());
+        assert!(Channel::public("orders").separator("/").build().is_err());
+        assert!(
+            Channel::public("orders")
+                .segment("a".repeat(200))
+                .build()
+                .is_err()
+        );
+
+        // Segments must not turn the name into another channel type
+        for builder in [
+            Channel::public("private").segment("orders"),
+            Channel::public("presence").segment("orders"),
+            Channel::public("private")
+                .segment("encrypted")
+                .segment("orders"),
+            Channel::public("cache").segment("orders"),
+            Channel::private("encrypted").segment("orders"),
+            Channel::presence("cache").segment("orders"),
+        ] {
+            assert!(builder.build().is_err());
+        }
+        assert!(
+            Channel::public("privately")
+                .segment("orders")
+                .build()
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_channel_base_and_full_name() {
+        let cases = [
+            ("orders", "orders"),
+            ("private-orders", "orders"),
+            ("presence-orders", "orders"),
+            ("private-encrypted-orders", "orders"),
+            ("cache-orders", "orders"),
+            ("private-cache-orders", "orders"),
+            ("presence-cache-orders", "orders"),
+            ("private-orders-private-x", "orders-private-x"),
+            ("privateorders", "privateorders"),
+            ("private-encrypted-cache-x", "cache-x"),
+        ];
+        for (raw, base) in cases {
+            let channel = Channel::from_string(raw).unwrap();
+            assert_eq!(channel.base_name(), base, "{}", raw);
+            assert_eq!(channel.full_name(), raw);
+            assert_eq!(channel.to_string(), raw);
+            assert_eq!(Channel::from_string(channel.full_name()).unwrap(), channel);
+            let json = crate::json::to_string(&channel).unwrap();
+            assert_eq!(
+                crate::json::from_str::<Channel>(&json).unwrap().full_name(),
+                raw
+            );
+        }
+    }
+
+    #[test]
+    fn test_channel_pattern() {
+        let pattern = ChannelPattern::prefix("presence-game-");
+        assert!(pattern.matches("presence-game-1"));
+        assert!(!pattern.matches("presence-lobby"));
+        assert!(pattern.matches_channel(&Channel::from_string("presence-game-2").unwrap()));
+        assert_eq!(
+            pattern
+                .query_params()
+                .get("filter_by_prefix")
+                .map(String::as_str),
+            Some("presence-game-")
+        );
+        assert_eq!(pattern.to_string(), "presence-game-*");
+
+        let pattern = ChannelPattern::exact("presence-lobby");
+        assert!(pattern.matches("presence-lobby"));
+        assert!(!pattern.matches("presence-lobby-2"));
+        assert_eq!(pattern.filter_by_prefix(), "presence-lobby");
+    }
+
+    #[test]
+    fn test_channel_macro() {
+        let channel = crate::channel!("presence-lobby");
+        assert_eq!(channel, Channel::from_string("presence-lobby").unwrap());
+        assert_eq!(
+            crate::channel!("private-encrypted-x").channel_type(),
+            ChannelType::Encrypted
+        );
+    }
+
+    #[test]
+    fn test_const_validation_matches_runtime() {
+        let long = "a".repeat(201);
+        let prefixed_long = format!("private-{}", "a".repeat(200));
+        let names = [
+            "test",
+            "private-test",
+            "private-",
+            "presence-cache-",
+            "cache-",
+            "",
+            "test channel",
+            "a/b",
+            "é",
+            "=@,.;_-",
+            long.as_str(),
+            prefixed_long.as_str(),
+        ];
+        for name in names {
+            assert_eq!(
+                is_valid_channel_name(name),
+                Channel::from_string(name).is_ok(),
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_list() {
+        let channels = Channel::parse_list("a, private-b,presence-c ").unwrap();
+        let names: Vec<String> = channels.iter().map(|c| c.full_name()).collect();
+        assert_eq!(names, vec!["a", "private-b", "presence-c"]);
+        assert!(Channel::parse_list("  ").unwrap().is_empty());
+
+        let err = Channel::parse_list("a,bad name,c").unwrap_err().to_string();
+        assert!(err.contains("index 1"), "{}", err);
+        assert!(err.contains("'bad name'"), "{}", err);
+        assert!(
+            Channel::parse_list("a,,b")
+                .unwrap_err()
+                .to_string()
+                .contains("index 1")
+        );
+    }
 }
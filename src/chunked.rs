@@ -0,0 +1,428 @@
+use crate::transport::Response;
+use crate::{Channel, Pusher, PusherError, Result, events, json};
+use events::{EventData, TriggerParams};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Prefix prepended to the event name of every chunk
+pub const CHUNKED_EVENT_PREFIX: &str = "chunked-";
+
+/// Bytes reserved for the `{id, index, chunk, final}` framing around each chunk
+const FRAME_OVERHEAD: usize = 128;
+
+/// Bytes of the `{"nonce":"","ciphertext":""}` wrapper and base64 nonce around encrypted data
+const ENCRYPTED_OVERHEAD: usize = 28 + 32;
+
+/// Bytes the secretbox MAC adds to the plaintext
+const MAC_BYTES: usize = 16;
+
+/// A single frame of a chunked event, as documented by Pusher
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChunkFrame {
+    pub id: String,
+    pub index: u32,
+    pub chunk: String,
+    #[serde(rename = "final")]
+    pub is_final: bool,
+}
+
+/// Splits data into serialized chunk frames whose size does not exceed `max_frame_bytes`
+pub fn chunk_event_data(data: &str, max_frame_bytes: usize) -> Result<Vec<String>> {
+    if max_frame_bytes <= FRAME_OVERHEAD {
+        return Err(PusherError::Validation {
+            message: format!(
+                "Chunk frame limit too small: {} bytes (min {} bytes)",
+                max_frame_bytes,
+                FRAME_OVERHEAD + 1
+            ),
+        });
+    }
+
+    let id = format!("{:016x}", rand::random::<u64>());
+    let budget = max_frame_bytes - FRAME_OVERHEAD;
+
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    let mut used = 0;
+    for (i, c) in data.char_indices() {
+        let cost = escaped_len(c);
+        if used > 0 && used + cost > budget {
+            chunks.push(&data[start..i]);
+            start = i;
+            used = 0;
+        }
+        used += cost;
+    }
+    chunks.push(&data[start..]);
+
+    let last = chunks.len() - 1;
+    chunks
+        .into_iter()
+        .enumerate()
+        .map(|(index, chunk)| {
+            let frame = ChunkFrame {
+                id: id.clone(),
+                index: index as u32,
+                chunk: chunk.to_string(),
+                is_final: index == last,
+            };
+            Ok(json::to_string(&frame)?)
+        })
+        .collect()
+}
+
+/// Largest plaintext whose encrypted payload fits within `max` bytes
+fn encrypted_plaintext_budget(max: usize) -> usize {
+    // Base64 turns every 3 bytes of ciphertext into 4
+    (max.saturating_sub(ENCRYPTED_OVERHEAD) / 4 * 3).saturating_sub(MAC_BYTES)
+}
+
+/// Length of a character once escaped inside a JSON string
+fn escaped_len(c: char) -> usize {
+    match c {
+        '"' | '\\' | '\n' | '\r' | '\t' | '\u{08}' | '\u{0c}' => 2,
+        c if (c as u32) < 0x20 => 6,
+        c => c.len_utf8(),
+    }
+}
+
+/// Triggers an event, splitting its data into `chunked-` events if it exceeds the payload limit
+///
+/// On encrypted channels the limit applies to the encrypted payload, so chunks are smaller.
+pub async fn trigger_chunked<D: Into<EventData>>(
+    pusher: &Pusher,
+    channels: &[Channel],
+    event_name: impl AsRef<str>,
+    data: D,
+    params: Option<&TriggerParams>,
+) -> Result<Vec<Response>> {
+    let data = data.into().to_string();
+    let event_name = event_name.as_ref();
+    let limit = pusher.config().max_event_payload_bytes();
+    // Encryption adds a nonce, a MAC and base64 expansion, so encrypted chunks get less room
+    let max = if channels.iter().any(|channel| channel.is_encrypted()) {
+        encrypted_plaintext_budget(limit)
+    } else {
+        limit
+    };
+
+    if data.len() <= max {
+        let response = events::trigger(pusher, channels, event_name, data, params).await?;
+        return Ok(vec![response]);
+    }
+
+    let chunked_name = format!("{}{}", CHUNKED_EVENT_PREFIX, event_name);
+    let mut responses = Vec::new();
+    for (index, frame) in chunk_event_data(&data, max)?.into_iter().enumerate() {
+        let params = params.map(|params| TriggerParams {
+            request_options: events::part_options(&params.request_options, index),
+            ..params.clone()
+        });
+        responses
+            .push(events::trigger(pusher, channels, &chunked_name, frame, params.as_ref()).await?);
+    }
+
+    Ok(responses)
+}
+
+/// Incomplete messages kept by a [`ChunkAssembler`] unless changed
+pub const DEFAULT_MAX_PENDING: usize = 64;
+
+/// Reassembles chunked events back into their original payloads
+///
+/// At most [`max_pending`](Self::max_pending) incomplete messages are kept; the oldest is
+/// dropped to make room for a new one, so lost chunks cannot grow it without bound.
+#[derive(Debug)]
+pub struct ChunkAssembler {
+    pending: HashMap<String, PendingMessage>,
+    max_pending: usize,
+    next_sequence: u64,
+}
+
+#[derive(Debug, Default)]
+struct PendingMessage {
+    chunks: BTreeMap<u32, String>,
+    final_index: Option<u32>,
+    sequence: u64,
+}
+
+impl PendingMessage {
+    /// Checks that `frame` agrees with the final chunk index, once one is known
+    fn check(&self, frame: &ChunkFrame) -> std::result::Result<(), String> {
+        let last = match (self.final_index, frame.is_final) {
+            (Some(last), true) if last != frame.index => {
+                return Err(format!(
+                    "conflicting final chunks {} and {}",
+                    last, frame.index
+                ));
+            }
+            (Some(last), _) => last,
+            (None, true) => frame.index,
+            (None, false) => return Ok(()),
+        };
+        let highest = self.chunks.keys().next_back().copied().unwrap_or(0);
+        if frame.index > last || highest > last {
+            return Err(format!(
+                "chunk {} is past the final chunk {}",
+                frame.index.max(highest),
+                last
+            ));
+        }
+        if !frame.is_final && frame.index == last {
+            return Err(format!("chunk {} conflicts with the final chunk", last));
+        }
+        Ok(())
+    }
+}
+
+impl Default for ChunkAssembler {
+    fn default() -> Self {
+        Self {
+            pending: HashMap::new(),
+            max_pending: DEFAULT_MAX_PENDING,
+            next_sequence: 0,
+        }
+    }
+}
+
+impl ChunkAssembler {
+    /// Creates an empty assembler
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how many incomplete messages are kept (default [`DEFAULT_MAX_PENDING`], min 1)
+    pub fn max_pending(mut self, max_pending: usize) -> Self {
+        self.max_pending = max_pending.max(1);
+        self
+    }
+
+    /// Adds a serialized chunk frame, returning the full payload once every chunk has arrived
+    pub fn push(&mut self, frame: &str) -> Result<Option<String>> {
+        let frame: ChunkFrame = json::from_str(frame)?;
+        self.push_frame(frame)
+    }
+
+    /// Adds a parsed chunk frame, returning the full payload once every chunk has arrived
+    ///
+    /// A frame whose index contradicts the message's final chunk is rejected, and the
+    /// message is discarded.
+    pub fn push_frame(&mut self, frame: ChunkFrame) -> Result<Option<String>> {
+        if !self.pending.contains_key(&frame.id) && self.pending.len() >= self.max_pending {
+            self.evict_oldest();
+        }
+        let sequence = self.next_sequence;
+        self.next_sequence += 1;
+        let pending = self
+            .pending
+            .entry(frame.id.clone())
+            .or_insert_with(|| PendingMessage {
+                sequence,
+                ..Default::default()
+            });
+
+        if let Err(message) = pending.check(&frame) {
+            self.pending.remove(&frame.id);
+            return Err(PusherError::Validation {
+                message: format!("Chunked message {}: {}", frame.id, message),
+            });
+        }
+        if frame.is_final {
+            pending.final_index = Some(frame.index);
+        }
+        pending.chunks.insert(frame.index, frame.chunk);
+
+        let complete = match pending.final_index {
+            Some(last) => pending.chunks.len() as u32 == last + 1,
+            None => false,
+        };
+        if !complete {
+            return Ok(None);
+        }
+
+        let pending = self.pending.remove(&frame.id).unwrap_or_default();
+        Ok(Some(pending.chunks.into_values().collect()))
+    }
+
+    /// Number of messages still waiting for chunks
+    pub fn pending_len(&self) -> usize {
+        self.pending.len()
+    }
+
+    fn evict_oldest(&mut self) {
+        let oldest = self
+            .pending
+            .iter()
+            .min_by_key(|(_, pending)| pending.sequence)
+            .map(|(id, _)| id.clone());
+        if let Some(id) = oldest {
+            self.pending.remove(&id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json::JsonValueTrait;
+    use crate::transport::CaptureTransport;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_chunk_and_reassemble() {
+        let data = "héllo \"world\" ".repeat(100);
+        let frames = chunk_event_data(&data, 256).unwrap();
+        assert!(frames.len() > 1);
+        assert!(frames.iter().all(|f| f.len() <= 256));
+
+        let mut assembler = ChunkAssembler::new();
+        let mut result = None;
+        for frame in frames.iter().rev() {
+            result = assembler.push(frame).unwrap();
+        }
+
+        assert_eq!(result, Some(data));
+        assert_eq!(assembler.pending_len(), 0);
+    }
+
+    #[test]
+    fn test_single_chunk_is_final() {
+        let frames = chunk_event_data("small", 1024).unwrap();
+        assert_eq!(frames.len(), 1);
+
+        let frame: ChunkFrame = json::from_str(&frames[0]).unwrap();
+        assert_eq!(frame.index, 0);
+        assert!(frame.is_final);
+    }
+
+    #[tokio::test]
+    async fn test_chunks_get_distinct_idempotency_keys() {
+        let transport = Arc::new(CaptureTransport::default());
+        let config = crate::Config::builder()
+            .app_id("123")
+            .key("key")
+            .secret("secret")
+            .max_event_payload_bytes(256)
+            .transport_arc(transport.clone())
+            .build()
+            .unwrap();
+        let pusher = Pusher::new(config).unwrap();
+        let params = TriggerParams::builder().idempotency_key("order-1").build();
+
+        let channels = [Channel::from_string("orders").unwrap()];
+        let data = "x".repeat(1000);
+        let responses = trigger_chunked(&pusher, &channels, "created", data, Some(&params))
+            .await
+            .unwrap();
+        assert!(responses.len() > 1);
+
+        let keys: Vec<String> = transport
+            .take()
+            .iter()
+            .filter_map(|request| {
+                request
+                    .headers
+                    .iter()
+                    .find(|(name, _)| name == crate::pusher::IDEMPOTENCY_KEY_HEADER)
+                    .map(|(_, value)| value.clone())
+            })
+            .collect();
+        let expected: Vec<String> = (0..responses.len())
+            .map(|index| format!("order-1-{}", index))
+            .collect();
+        assert_eq!(keys, expected);
+    }
+
+    #[cfg(feature = "encryption")]
+    #[tokio::test]
+    async fn test_encrypted_chunks_fit_after_encryption() {
+        let transport = Arc::new(CaptureTransport::default());
+        let config = crate::Config::builder()
+            .app_id("123")
+            .key("key")
+            .secret("secret")
+            .encryption_master_key(vec![7u8; 32])
+            .unwrap()
+            .max_event_payload_bytes(512)
+            .transport_arc(transport.clone())
+            .build()
+            .unwrap();
+        let pusher = Pusher::new(config).unwrap();
+
+        let channel = "private-encrypted-orders";
+        let channels = [Channel::from_string(channel).unwrap()];
+        let data = "x".repeat(2000);
+        let responses = trigger_chunked(&pusher, &channels, "created", data.clone(), None)
+            .await
+            .unwrap();
+        assert!(responses.len() > 1);
+
+        let mut assembler = ChunkAssembler::new();
+        let mut result = None;
+        for request in transport.take() {
+            let body: json::Value = json::from_str(request.body.as_deref().unwrap()).unwrap();
+            let encrypted = body["data"].as_str().unwrap();
+            assert!(encrypted.len() <= 512);
+            let payload = events::EncryptedPayload::parse(encrypted).unwrap();
+            let frame = pusher.decrypt_event(channel, &payload).unwrap().to_string();
+            result = assembler.push(&frame).unwrap();
+        }
+        assert_eq!(result, Some(data));
+    }
+
+    #[test]
+    fn test_encrypted_plaintext_budget() {
+        // 4 * ceil((n + 16) / 3) + 60 <= max
+        assert_eq!(encrypted_plaintext_budget(512), 323);
+        assert_eq!(encrypted_plaintext_budget(40), 0);
+    }
+
+    #[test]
+    fn test_frame_limit_too_small() {
+        assert!(chunk_event_data("data", 64).is_err());
+    }
+
+    fn frame(id: &str, index: u32, is_final: bool) -> ChunkFrame {
+        ChunkFrame {
+            id: id.to_string(),
+            index,
+            chunk: index.to_string(),
+            is_final,
+        }
+    }
+
+    #[test]
+    fn test_rejects_inconsistent_frames() {
+        let mut assembler = ChunkAssembler::new();
+        assembler.push_frame(frame("a", 0, false)).unwrap();
+        assembler.push_frame(frame("a", 2, true)).unwrap();
+        assert!(assembler.push_frame(frame("a", 3, false)).is_err());
+        assert_eq!(assembler.pending_len(), 0);
+
+        assembler.push_frame(frame("b", 1, true)).unwrap();
+        assert!(assembler.push_frame(frame("b", 2, true)).is_err());
+
+        assembler.push_frame(frame("c", 5, false)).unwrap();
+        assert!(assembler.push_frame(frame("c", 1, true)).is_err());
+
+        assembler.push_frame(frame("d", 1, true)).unwrap();
+        assert!(assembler.push_frame(frame("d", 1, false)).is_err());
+        assert_eq!(assembler.pending_len(), 0);
+    }
+
+    #[test]
+    fn test_max_pending_evicts_oldest() {
+        let mut assembler = ChunkAssembler::new().max_pending(2);
+        assembler.push_frame(frame("a", 1, true)).unwrap();
+        assembler.push_frame(frame("b", 1, true)).unwrap();
+        assembler.push_frame(frame("c", 1, true)).unwrap();
+        assert_eq!(assembler.pending_len(), 2);
+
+        // "a" was dropped, so its first chunk starts a new message
+        assert_eq!(assembler.push_frame(frame("a", 0, false)).unwrap(), None);
+        assert_eq!(
+            assembler.push_frame(frame("c", 0, false)).unwrap(),
+            Some("01".to_string())
+        );
+    }
+}
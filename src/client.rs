@@ -0,0 +1,116 @@
+//! Object-safe client trait for dependency injection
+//!
+//! Application code can take `&dyn PusherClient` (or `Arc<dyn PusherClient>`) instead of
+//! [`Pusher`], then substitute fakes in tests or wrap the client in decorators (metrics,
+//! multi-region fan-out).
+
+use crate::auth::SocketAuth;
+use crate::events::{BatchEvent, EventData, TriggerParams};
+use crate::interceptor::BoxFuture;
+use crate::json::Value;
+use crate::transport::Response;
+use crate::{Channel, Pusher, Result};
+use std::collections::BTreeMap;
+
+/// Public operations of the Pusher client
+pub trait PusherClient: Send + Sync {
+    /// Triggers an event on channels
+    fn trigger<'a>(
+        &'a self,
+        channels: &'a [Channel],
+        event: &'a str,
+        data: EventData,
+        params: Option<TriggerParams>,
+    ) -> BoxFuture<'a, Result<Response>>;
+
+    /// Triggers a batch of events
+    fn trigger_batch(&self, batch: Vec<BatchEvent>) -> BoxFuture<'_, Result<Response>>;
+
+    /// Sends an event to a user
+    fn send_to_user<'a>(
+        &'a self,
+        user_id: &'a str,
+        event: &'a str,
+        data: EventData,
+    ) -> BoxFuture<'a, Result<Response>>;
+
+    /// Authorizes a channel subscription
+    fn authorize_channel<'a>(
+        &'a self,
+        socket_id: &'a str,
+        channel: &'a Channel,
+        data: Option<&'a Value>,
+    ) -> BoxFuture<'a, Result<SocketAuth>>;
+
+    /// Makes a GET request, e.g. to query `/channels` or `/channels/{name}`
+    fn get<'a>(
+        &'a self,
+        path: &'a str,
+        params: Option<&'a BTreeMap<String, String>>,
+    ) -> BoxFuture<'a, Result<Response>>;
+}
+
+impl PusherClient for Pusher {
+    fn trigger<'a>(
+        &'a self,
+        channels: &'a [Channel],
+        event: &'a str,
+        data: EventData,
+        params: Option<TriggerParams>,
+    ) -> BoxFuture<'a, Result<Response>> {
+        Box::pin(Pusher::trigger(self, channels, event, data, params))
+    }
+
+    fn trigger_batch(&self, batch: Vec<BatchEvent>) -> BoxFuture<'_, Result<Response>> {
+        Box::pin(Pusher::trigger_batch(self, batch))
+    }
+
+    fn send_to_user<'a>(
+        &'a self,
+        user_id: &'a str,
+        event: &'a str,
+        data: EventData,
+    ) -> BoxFuture<'a, Result<Response>> {
+        Box::pin(Pusher::send_to_user(self, user_id, event, data))
+    }
+
+    fn authorize_channel<'a>(
+        &'a self,
+        socket_id: &'a str,
+        channel: &'a Channel,
+        data: Option<&'a Value>,
+    ) -> BoxFuture<'a, Result<SocketAuth>> {
+        Box::pin(self.authorize_channel_async(socket_id, channel, data))
+    }
+
+    fn get<'a>(
+        &'a self,
+        path: &'a str,
+        params: Option<&'a BTreeMap<String, String>>,
+    ) -> BoxFuture<'a, Result<Response>> {
+        Box::pin(Pusher::get(self, path, params))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Config, PusherError};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_pusher_as_trait_object() {
+        let client: Arc<dyn PusherClient> =
+            Arc::new(Pusher::new(Config::new("123", "key", "secret")).unwrap());
+        let channel = Channel::from_string("private-orders").unwrap();
+
+        let auth = client
+            .authorize_channel("123.456", &channel, None)
+            .await
+            .unwrap();
+        assert!(auth.auth.starts_with("key:"));
+
+        let result = client.trigger(&[], "created", "{}".into(), None).await;
+        assert!(matches!(result, Err(PusherError::Validation { .. })));
+    }
+}
@@ -0,0 +1,172 @@
+//! Detection of redelivered webhooks
+
+use crate::Webhook;
+use crate::interceptor::BoxFuture;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+/// Default number of deliveries remembered by [`MemoryDeliveryStore`]
+pub const DEFAULT_DELIVERY_CAPACITY: usize = 10_000;
+
+/// Storage for the ids of webhook deliveries that have already been seen
+///
+/// Implement this for a shared store (e.g. Redis) when several receivers handle
+/// deliveries for the same app.
+pub trait DeliveryStore: Send + Sync {
+    /// Records the delivery id, returning `true` if it had already been recorded
+    fn check_and_insert<'a>(&'a self, delivery_id: &'a str) -> BoxFuture<'a, bool>;
+}
+
+/// In-memory store that forgets the least recently seen deliveries beyond its capacity
+#[derive(Debug)]
+pub struct MemoryDeliveryStore {
+    capacity: usize,
+    state: Mutex<LruState>,
+}
+
+#[derive(Debug, Default)]
+struct LruState {
+    tick: u64,
+    last_seen: HashMap<String, u64>,
+    by_tick: BTreeMap<u64, String>,
+}
+
+impl MemoryDeliveryStore {
+    /// Creates a store remembering at most `capacity` deliveries
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            state: Mutex::new(LruState::default()),
+        }
+    }
+
+    /// Number of deliveries currently remembered
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().last_seen.len()
+    }
+
+    /// Checks whether no deliveries are remembered
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn touch(&self, delivery_id: &str) -> bool {
+        let mut state = self.state.lock().unwrap();
+        state.tick += 1;
+        let tick = state.tick;
+
+        let seen = match state.last_seen.insert(delivery_id.to_string(), tick) {
+            Some(previous) => {
+                state.by_tick.remove(&previous);
+                true
+            }
+            None => false,
+        };
+        state.by_tick.insert(tick, delivery_id.to_string());
+
+        while state.last_seen.len() > self.capacity {
+            if let Some((_, oldest)) = state.by_tick.pop_first() {
+                state.last_seen.remove(&oldest);
+            }
+        }
+
+        seen
+    }
+}
+
+impl Default for MemoryDeliveryStore {
+    fn default() -> Self {
+        Self::new(DEFAULT_DELIVERY_CAPACITY)
+    }
+}
+
+impl DeliveryStore for MemoryDeliveryStore {
+    fn check_and_insert<'a>(&'a self, delivery_id: &'a str) -> BoxFuture<'a, bool> {
+        let seen = self.touch(delivery_id);
+        Box::pin(async move { seen })
+    }
+}
+
+/// Answers whether a webhook delivery has already been processed
+///
+/// Deliveries are identified by a SHA-256 hash of the raw body together with `time_ms`.
+#[derive(Clone)]
+pub struct WebhookDeduplicator {
+    store: Arc<dyn DeliveryStore>,
+}
+
+impl fmt::Debug for WebhookDeduplicator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebhookDeduplicator")
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for WebhookDeduplicator {
+    fn default() -> Self {
+        Self::new(MemoryDeliveryStore::default())
+    }
+}
+
+impl WebhookDeduplicator {
+    /// Creates a deduplicator backed by the given store
+    pub fn new(store: impl DeliveryStore + 'static) -> Self {
+        Self {
+            store: Arc::new(store),
+        }
+    }
+
+    /// Computes the delivery id of a webhook
+    pub fn delivery_id(webhook: &Webhook) -> String {
+        let time_ms = webhook.get_data().map(|d| d.time_ms).unwrap_or_default();
+
+        let mut hasher = Sha256::new();
+        hasher.update(webhook.body_bytes());
+        format!("{}:{}", time_ms, hex::encode(hasher.finalize()))
+    }
+
+    /// Records the delivery, returning `true` if it was already seen
+    pub async fn is_duplicate(&self, webhook: &Webhook) -> bool {
+        let id = Self::delivery_id(webhook);
+        self.store.check_and_insert(&id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Token;
+
+    fn webhook(body: &str) -> Webhook {
+        let token = Token::new("test_key", "test_secret");
+        let mut headers = std::collections::BTreeMap::new();
+        headers.insert("content-type".to_string(), "application/json".to_string());
+        Webhook::new(&token, &headers, body)
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_delivery() {
+        let dedup = WebhookDeduplicator::default();
+        let first = webhook(r#"{"time_ms": 1, "events": []}"#);
+        let second = webhook(r#"{"time_ms": 2, "events": []}"#);
+
+        assert!(!dedup.is_duplicate(&first).await);
+        assert!(!dedup.is_duplicate(&second).await);
+        assert!(dedup.is_duplicate(&first).await);
+    }
+
+    #[test]
+    fn test_memory_store_evicts_least_recent() {
+        let store = MemoryDeliveryStore::new(2);
+        assert!(!store.touch("a"));
+        assert!(!store.touch("b"));
+        assert!(store.touch("a"));
+        assert!(!store.touch("c"));
+
+        assert_eq!(store.len(), 2);
+        assert!(store.touch("a"));
+        assert!(!store.touch("b"));
+    }
+}
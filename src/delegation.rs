@@ -0,0 +1,410 @@
+//! Short-lived delegation tokens for splitting channel authorization across services
+//!
+//! A service holding the app secret issues a token granting access to some channels with
+//! [`DelegationIssuer`]. Services without the secret pass it along (for example to the browser),
+//! and the auth endpoint checks it with [`DelegationVerifier::authorize`] before calling
+//! [`Pusher::authorize_channel`](crate::Pusher::authorize_channel).
+//!
+//! Tokens are compact `HS256` JWTs. They are keyed with the hex HMAC-SHA256 of
+//! [`KEY_DERIVATION_INFO`] under the app secret rather than the secret itself, so a delegation
+//! token can never double as a channel auth or request signature.
+
+use crate::clock::{Clock, ClockHandle};
+use crate::errors::DelegationError;
+use crate::{Token, json};
+use base64::Engine as _;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Default lifetime of issued tokens
+pub const DEFAULT_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// Default allowed clock skew between issuer and verifier
+pub const DEFAULT_LEEWAY: Duration = Duration::from_secs(30);
+
+/// Input signed with the app secret to derive the delegation signing key
+pub const KEY_DERIVATION_INFO: &str = "pushers-delegation-v1";
+
+const ALGORITHM: &str = "HS256";
+
+/// Channels a delegation token grants, optionally bound to a user and socket
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DelegationGrant {
+    channels: Vec<String>,
+    subject: Option<String>,
+    socket_id: Option<String>,
+}
+
+impl DelegationGrant {
+    /// Grants the given full channel names; an entry ending in `*` grants every channel with
+    /// that prefix
+    pub fn new<I, S>(channels: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            channels: channels.into_iter().map(Into::into).collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Records who the grant is for, typically a user ID
+    pub fn subject(mut self, subject: impl Into<String>) -> Self {
+        self.subject = Some(subject.into());
+        self
+    }
+
+    /// Restricts the grant to one socket
+    pub fn socket_id(mut self, socket_id: impl Into<String>) -> Self {
+        self.socket_id = Some(socket_id.into());
+        self
+    }
+}
+
+/// Verified contents of a delegation token
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DelegationClaims {
+    /// Who the grant is for
+    #[serde(rename = "sub", default, skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    /// Socket the grant is bound to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub socket_id: Option<String>,
+    /// Granted channel names and `*`-terminated prefixes
+    pub channels: Vec<String>,
+    /// Unix time the token was issued
+    #[serde(rename = "iat")]
+    pub issued_at: u64,
+    /// Unix time after which the token is rejected
+    #[serde(rename = "exp")]
+    pub expires_at: u64,
+}
+
+impl DelegationClaims {
+    /// Whether the grant covers a full channel name
+    pub fn allows_channel(&self, channel: &str) -> bool {
+        self.channels
+            .iter()
+            .any(|granted| match granted.strip_suffix('*') {
+                Some(prefix) => channel.starts_with(prefix),
+                None => channel == granted,
+            })
+    }
+
+    /// Whether the grant may be used by a socket
+    pub fn allows_socket(&self, socket_id: &str) -> bool {
+        self.socket_id.as_deref().is_none_or(|id| id == socket_id)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Header {
+    alg: String,
+    typ: String,
+    kid: String,
+}
+
+/// Issues delegation tokens; requires the app secret
+#[derive(Debug, Clone)]
+pub struct DelegationIssuer {
+    token: Token,
+    ttl: Duration,
+    clock: ClockHandle,
+}
+
+impl DelegationIssuer {
+    /// Creates an issuer signing with the given token
+    ///
+    /// Fails if the token has no secret, such as the token of a config with a custom signer.
+    pub fn new(token: Token) -> crate::Result<Self> {
+        Ok(Self {
+            token: delegation_token(&token)?,
+            ttl: DEFAULT_TTL,
+            clock: ClockHandle::default(),
+        })
+    }
+
+    /// Sets how long issued tokens stay valid
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Sets the clock used for `iat` and `exp`
+    pub fn clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = ClockHandle(clock);
+        self
+    }
+
+    /// Issues a token for the grant
+    pub fn issue(&self, grant: &DelegationGrant) -> crate::Result<String> {
+        let issued_at = self.clock.unix_secs();
+        let header = Header {
+            alg: ALGORITHM.to_string(),
+            typ: "JWT".to_string(),
+            kid: self.token.key.clone(),
+        };
+        let claims = DelegationClaims {
+            subject: grant.subject.clone(),
+            socket_id: grant.socket_id.clone(),
+            channels: grant.channels.clone(),
+            issued_at,
+            expires_at: issued_at.saturating_add(self.ttl.as_secs()),
+        };
+
+        let signing_input = format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(json::to_string(&header)?),
+            URL_SAFE_NO_PAD.encode(json::to_string(&claims)?)
+        );
+        let signature = URL_SAFE_NO_PAD.encode(self.token.sign_bytes(signing_input.as_bytes()));
+        Ok(format!("{}.{}", signing_input, signature))
+    }
+}
+
+/// Verifies delegation tokens at the auth endpoint
+#[derive(Debug, Clone)]
+pub struct DelegationVerifier {
+    tokens: Vec<Token>,
+    leeway: Duration,
+    clock: ClockHandle,
+}
+
+impl DelegationVerifier {
+    /// Creates a verifier for tokens issued with the given token
+    ///
+    /// Fails if the token has no secret.
+    pub fn new(token: Token) -> crate::Result<Self> {
+        Self::with_tokens(vec![token])
+    }
+
+    /// Creates a verifier accepting tokens from any of the given apps, matched by key
+    ///
+    /// Fails if any token has no secret.
+    pub fn with_tokens(tokens: Vec<Token>) -> crate::Result<Self> {
+        Ok(Self {
+            tokens: tokens
+                .iter()
+                .map(delegation_token)
+                .collect::<crate::Result<_>>()?,
+            leeway: DEFAULT_LEEWAY,
+            clock: ClockHandle::default(),
+        })
+    }
+
+    /// Sets the allowed clock skew for `iat` and `exp`
+    pub fn leeway(mut self, leeway: Duration) -> Self {
+        self.leeway = leeway;
+        self
+    }
+
+    /// Sets the clock used for the expiry check
+    pub fn clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = ClockHandle(clock);
+        self
+    }
+
+    /// Checks the signature and lifetime of a token and returns its claims
+    pub fn verify(&self, token: &str) -> Result<DelegationClaims, DelegationError> {
+        let mut parts = token.split('.');
+        let (Some(header), Some(claims), Some(signature), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(DelegationError::Malformed);
+        };
+
+        let header: Header = decode_part(header)?;
+        if header.alg != ALGORITHM {
+            return Err(DelegationError::UnsupportedAlgorithm(header.alg));
+        }
+        let key = self
+            .tokens
+            .iter()
+            .find(|t| t.key == header.kid)
+            .ok_or(DelegationError::UnknownKey(header.kid))?;
+
+        let signature = URL_SAFE_NO_PAD
+            .decode(signature)
+            .map_err(|_| DelegationError::Malformed)?;
+        let signing_input = &token[..token.rfind('.').unwrap_or_default()];
+        if !key.verify_bytes(signing_input.as_bytes(), &signature) {
+            return Err(DelegationError::SignatureMismatch);
+        }
+
+        let claims: DelegationClaims = decode_part(claims)?;
+        let now = self.clock.unix_secs();
+        let leeway = self.leeway.as_secs();
+        if claims.issued_at > now.saturating_add(leeway) {
+            return Err(DelegationError::NotYetValid {
+                issued_at: claims.issued_at,
+            });
+        }
+        if now > claims.expires_at.saturating_add(leeway) {
+            return Err(DelegationError::Expired {
+                expires_at: claims.expires_at,
+            });
+        }
+        Ok(claims)
+    }
+
+    /// Verifies a token and checks that it grants `channel` to `socket_id`
+    pub fn authorize(
+        &self,
+        token: &str,
+        socket_id: &str,
+        channel: &str,
+    ) -> Result<DelegationClaims, DelegationError> {
+        let claims = self.verify(token)?;
+        if !claims.allows_socket(socket_id) {
+            return Err(DelegationError::SocketMismatch);
+        }
+        if !claims.allows_channel(channel) {
+            return Err(DelegationError::ChannelNotGranted(channel.to_string()));
+        }
+        Ok(claims)
+    }
+}
+
+/// Token keyed with the delegation keys derived from the current and retired secrets
+///
+/// An empty secret would derive a key anyone can compute, so tokens without one are refused.
+fn delegation_token(token: &Token) -> crate::Result<Token> {
+    token.require_secret()?;
+    let derive = |secret: &str| Token::new("", secret).sign(KEY_DERIVATION_INFO);
+    Ok(token.retired_secrets().fold(
+        Token::new(token.key.clone(), derive(&token.secret_string())),
+        |derived, retired| derived.with_retired_secret(derive(retired)),
+    ))
+}
+
+fn decode_part<T: serde::de::DeserializeOwned>(part: &str) -> Result<T, DelegationError> {
+    let bytes = URL_SAFE_NO_PAD
+        .decode(part)
+        .map_err(|_| DelegationError::Malformed)?;
+    json::from_slice(&bytes).map_err(|_| DelegationError::Malformed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::FixedClock;
+    use crate::config::Config;
+
+    fn issuer(now: u64) -> DelegationIssuer {
+        DelegationIssuer::new(Token::new("key", "secret"))
+            .unwrap()
+            .clock(Arc::new(FixedClock::from_unix_secs(now)))
+    }
+
+    fn verifier(now: u64) -> DelegationVerifier {
+        DelegationVerifier::new(Token::new("key", "secret"))
+            .unwrap()
+            .clock(Arc::new(FixedClock::from_unix_secs(now)))
+    }
+
+    #[test]
+    fn test_issue_and_authorize() {
+        let grant = DelegationGrant::new(["private-orders", "presence-game-*"])
+            .subject("user-1")
+            .socket_id("123.456");
+        let token = issuer(1_000).issue(&grant).unwrap();
+
+        let claims = verifier(1_100)
+            .authorize(&token, "123.456", "presence-game-7")
+            .unwrap();
+        assert_eq!(claims.subject.as_deref(), Some("user-1"));
+        assert_eq!(claims.expires_at, 1_000 + DEFAULT_TTL.as_secs());
+
+        assert_eq!(
+            verifier(1_100).authorize(&token, "123.456", "private-other"),
+            Err(DelegationError::ChannelNotGranted(
+                "private-other".to_string()
+            ))
+        );
+        assert_eq!(
+            verifier(1_100).authorize(&token, "999.999", "private-orders"),
+            Err(DelegationError::SocketMismatch)
+        );
+    }
+
+    #[test]
+    fn test_rejects_expired_and_tampered_tokens() {
+        let token = issuer(1_000)
+            .ttl(Duration::from_secs(60))
+            .issue(&DelegationGrant::new(["private-a"]))
+            .unwrap();
+        assert_eq!(
+            verifier(1_200).verify(&token),
+            Err(DelegationError::Expired { expires_at: 1_060 })
+        );
+
+        let forged = DelegationIssuer::new(Token::new("key", "other"))
+            .unwrap()
+            .issue(&DelegationGrant::new(["private-a"]))
+            .unwrap();
+        assert_eq!(
+            verifier(1_000).verify(&forged),
+            Err(DelegationError::SignatureMismatch)
+        );
+        assert_eq!(
+            verifier(1_000).verify("not-a-token"),
+            Err(DelegationError::Malformed)
+        );
+
+        let mut parts: Vec<&str> = token.split('.').collect();
+        let widened =
+            URL_SAFE_NO_PAD.encode(r#"{"channels":["private-*"],"iat":1000,"exp":9999999999}"#);
+        parts[1] = &widened;
+        assert_eq!(
+            verifier(1_000).verify(&parts.join(".")),
+            Err(DelegationError::SignatureMismatch)
+        );
+    }
+
+    #[test]
+    fn test_extreme_ttl_and_leeway_saturate() {
+        let token = issuer(1_000)
+            .ttl(Duration::MAX)
+            .issue(&DelegationGrant::new(["private-a"]))
+            .unwrap();
+        let claims = verifier(1_000)
+            .leeway(Duration::MAX)
+            .verify(&token)
+            .unwrap();
+        assert_eq!(claims.expires_at, u64::MAX);
+    }
+
+    #[test]
+    fn test_accepts_retired_secret() {
+        let token = issuer(1_000)
+            .issue(&DelegationGrant::new(["private-a"]))
+            .unwrap();
+        let rotated =
+            DelegationVerifier::new(Token::new("key", "new-secret").with_retired_secret("secret"))
+                .unwrap()
+                .clock(Arc::new(FixedClock::from_unix_secs(1_000)));
+        assert!(rotated.verify(&token).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_tokens_without_secret() {
+        let signer_only = Config::builder()
+            .app_id("123")
+            .key("key")
+            .signer(Token::new("key", "secret"))
+            .build()
+            .unwrap();
+        assert!(DelegationVerifier::new(signer_only.token().clone()).is_err());
+        assert!(DelegationIssuer::new(signer_only.token().clone()).is_err());
+        assert!(
+            DelegationVerifier::with_tokens(vec![
+                Token::new("key", "secret"),
+                Token::new("other", ""),
+            ])
+            .is_err()
+        );
+    }
+}
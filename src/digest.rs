@@ -0,0 +1,86 @@
+//! Body digest used for the `body_md5` request parameter
+//!
+//! The Pusher protocol requires an MD5 of the request body. MD5 is used for compatibility,
+//! not security; environments that cannot link the pure-Rust `md5` crate (e.g. FIPS builds)
+//! can supply their own provider, such as OpenSSL EVP.
+
+use std::fmt;
+use std::sync::Arc;
+
+/// Computes the MD5 digest of request bodies
+pub trait BodyDigest: Send + Sync {
+    /// Returns the raw MD5 digest of the body
+    fn md5(&self, body: &[u8]) -> [u8; 16];
+}
+
+/// Pure-Rust MD5 provider backed by the `md5` crate
+#[cfg(feature = "md5")]
+#[cfg_attr(docsrs, doc(cfg(feature = "md5")))]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Md5Digest;
+
+#[cfg(feature = "md5")]
+impl BodyDigest for Md5Digest {
+    fn md5(&self, body: &[u8]) -> [u8; 16] {
+        md5::compute(body).0
+    }
+}
+
+/// Shared handle to the body digest provider
+#[derive(Clone)]
+pub(crate) struct DigestHandle(pub(crate) Arc<dyn BodyDigest>);
+
+impl DigestHandle {
+    /// Lowercase hex MD5 of the body, as sent in `body_md5`
+    pub(crate) fn md5_hex(&self, body: &[u8]) -> String {
+        hex::encode(self.0.md5(body))
+    }
+
+    /// The built-in MD5 provider, or a configuration error when the `md5` feature is disabled
+    #[cfg(feature = "md5")]
+    pub(crate) fn builtin() -> crate::Result<Self> {
+        Ok(Self(Arc::new(Md5Digest)))
+    }
+
+    #[cfg(not(feature = "md5"))]
+    pub(crate) fn builtin() -> crate::Result<Self> {
+        Err(crate::PusherError::Config {
+            message: "A body digest provider is required when the md5 feature is disabled"
+                .to_string(),
+        })
+    }
+}
+
+impl fmt::Debug for DigestHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "BodyDigest")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FixedDigest;
+
+    impl BodyDigest for FixedDigest {
+        fn md5(&self, _body: &[u8]) -> [u8; 16] {
+            [0xab; 16]
+        }
+    }
+
+    #[cfg(feature = "md5")]
+    #[test]
+    fn test_md5_digest() {
+        assert_eq!(
+            DigestHandle::builtin().unwrap().md5_hex(b"hello"),
+            "5d41402abc4b2a76b9719d911017c592"
+        );
+    }
+
+    #[test]
+    fn test_custom_digest() {
+        let handle = DigestHandle(Arc::new(FixedDigest));
+        assert_eq!(handle.md5_hex(b"hello"), "ab".repeat(16));
+    }
+}
@@ -0,0 +1,397 @@
+//! Key derivation and cryptographic backends for end-to-end encrypted channels
+
+use crate::interceptor::BoxFuture;
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex, RwLock};
+use zeroize::Zeroizing;
+
+/// Derives the shared secret of an encrypted channel from the encryption master key
+///
+/// This is the value returned base64-encoded as `shared_secret` when authorizing a
+/// `private-encrypted-` channel, and only requires the master key, not API credentials.
+pub fn derive_shared_secret(master_key: &[u8], channel: &str) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(channel.as_bytes());
+    hasher.update(master_key);
+    hasher.finalize().into()
+}
+
+/// Key derivation used for encrypted channel shared secrets
+///
+/// Every server that authorizes or triggers events on the app's encrypted channels must use the
+/// same derivation, since clients only receive the derived secret from the auth endpoint.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum KeyDerivation {
+    /// `SHA256(channel || master_key)`, as used by Pusher Channels and all official server SDKs
+    #[default]
+    Sha256,
+    /// HKDF-SHA256 with the master key as input keying material and the channel name as info
+    ///
+    /// **Not interoperable** with other Pusher server libraries. Only select it for self-hosted
+    /// deployments where every component uses this crate (or the same HKDF parameters).
+    Hkdf,
+}
+
+impl KeyDerivation {
+    /// Derives the shared secret of an encrypted channel from the encryption master key
+    pub fn derive(self, master_key: &[u8], channel: &str) -> [u8; 32] {
+        match self {
+            Self::Sha256 => derive_shared_secret(master_key, channel),
+            Self::Hkdf => {
+                let mut secret = [0u8; 32];
+                hkdf::Hkdf::<Sha256>::new(None, master_key)
+                    .expand(channel.as_bytes(), &mut secret)
+                    .expect("32 bytes is a valid HKDF-SHA256 output length");
+                secret
+            }
+        }
+    }
+}
+
+/// Encryption master key, zeroized on drop
+pub type MasterKey = Zeroizing<[u8; 32]>;
+
+/// Source of the encryption master key, e.g. Vault or a KMS
+///
+/// Keys are fetched on first use by async operations, or explicitly with
+/// [`Pusher::refresh_master_key`](crate::Pusher::refresh_master_key), which should also be
+/// called whenever the key rotates.
+pub trait MasterKeyProvider: Send + Sync {
+    /// Fetches the current master key
+    fn current(&self) -> BoxFuture<'_, crate::Result<MasterKey>>;
+
+    /// Fetches the previous master key, still accepted when decrypting during rotation
+    fn previous(&self) -> BoxFuture<'_, crate::Result<Option<MasterKey>>> {
+        Box::pin(async { Ok(None) })
+    }
+}
+
+/// Shared handle to the master key provider stored in the configuration
+#[derive(Clone)]
+pub(crate) struct MasterKeyProviderHandle(pub(crate) Arc<dyn MasterKeyProvider>);
+
+impl fmt::Debug for MasterKeyProviderHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "MasterKeyProvider")
+    }
+}
+
+/// Master keys last fetched from a [`MasterKeyProvider`]
+#[derive(Clone)]
+pub(crate) struct LoadedMasterKeys {
+    /// Incremented on every refresh; version 0 is reserved for a static master key
+    pub(crate) version: u64,
+    pub(crate) current: MasterKey,
+    #[cfg_attr(not(feature = "encryption"), allow(dead_code))]
+    pub(crate) previous: Option<MasterKey>,
+}
+
+/// Latest master keys fetched by a client
+#[derive(Default)]
+pub(crate) struct MasterKeyState(RwLock<Option<LoadedMasterKeys>>);
+
+impl MasterKeyState {
+    pub(crate) fn get(&self) -> Option<LoadedMasterKeys> {
+        self.0.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Independent state starting from the currently loaded keys
+    pub(crate) fn snapshot(&self) -> Self {
+        Self(RwLock::new(self.get()))
+    }
+
+    pub(crate) fn set(&self, current: MasterKey, previous: Option<MasterKey>) {
+        let mut state = self.0.write().unwrap_or_else(|e| e.into_inner());
+        let version = state.as_ref().map_or(1, |keys| keys.version + 1);
+        *state = Some(LoadedMasterKeys {
+            version,
+            current,
+            previous,
+        });
+    }
+}
+
+/// Maximum number of channel secrets kept by [`SharedSecretCache`] before it is cleared
+const SHARED_SECRET_CACHE_CAPACITY: usize = 4096;
+
+type SecretsByChannel = HashMap<String, Zeroizing<[u8; 32]>>;
+
+/// Cache of derived channel shared secrets for one master key version
+///
+/// Entries are dropped (and zeroized) when the master key version changes or the capacity is
+/// reached.
+#[derive(Debug, Default)]
+pub(crate) struct SharedSecretCache {
+    state: Mutex<(u64, SecretsByChannel)>,
+}
+
+impl SharedSecretCache {
+    /// Returns the cached secret for the channel, deriving and storing it on a miss
+    pub(crate) fn get_or_derive(
+        &self,
+        key_version: u64,
+        channel: &str,
+        derive: impl FnOnce() -> [u8; 32],
+    ) -> Zeroizing<[u8; 32]> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let (version, secrets) = &mut *state;
+        if *version != key_version {
+            secrets.clear();
+            *version = key_version;
+        }
+        if let Some(secret) = secrets.get(channel) {
+            return secret.clone();
+        }
+        if secrets.len() >= SHARED_SECRET_CACHE_CAPACITY {
+            secrets.clear();
+        }
+        let secret = Zeroizing::new(derive());
+        secrets.insert(channel.to_string(), secret.clone());
+        secret
+    }
+}
+
+/// Derives the shared secret of an encrypted channel, base64-encoded
+pub fn derive_shared_secret_base64(master_key: &[u8], channel: &str) -> String {
+    BASE64.encode(derive_shared_secret(master_key, channel))
+}
+
+/// Cryptographic primitives used for encrypted channels
+///
+/// Pusher clients expect NaCl secretbox (XSalsa20-Poly1305) ciphertexts, as produced by the
+/// default [`SecretboxBackend`]. Custom implementations, e.g. hardware-backed or audited ones,
+/// must stay compatible with it.
+#[cfg(feature = "encryption")]
+#[cfg_attr(docsrs, doc(cfg(feature = "encryption")))]
+pub trait EncryptionBackend: Send + Sync {
+    /// Generates a fresh random nonce
+    fn generate_nonce(&self) -> crate::Result<[u8; 24]>;
+
+    /// Encrypts and authenticates the plaintext
+    fn seal(&self, key: &[u8; 32], nonce: &[u8; 24], plaintext: &[u8]) -> crate::Result<Vec<u8>>;
+
+    /// Verifies and decrypts the ciphertext
+    fn open(&self, key: &[u8; 32], nonce: &[u8; 24], ciphertext: &[u8]) -> crate::Result<Vec<u8>>;
+
+    /// Derives the shared secret of an encrypted channel from the master key
+    fn derive_shared_secret(&self, master_key: &[u8], channel: &str) -> [u8; 32] {
+        derive_shared_secret(master_key, channel)
+    }
+}
+
+/// Default secretbox backend: pure Rust, or libsodium with the `sodiumoxide` feature
+#[cfg(feature = "encryption")]
+#[cfg_attr(docsrs, doc(cfg(feature = "encryption")))]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SecretboxBackend;
+
+#[cfg(feature = "encryption")]
+impl EncryptionBackend for SecretboxBackend {
+    fn generate_nonce(&self) -> crate::Result<[u8; 24]> {
+        secretbox::random_nonce()
+    }
+
+    fn seal(&self, key: &[u8; 32], nonce: &[u8; 24], plaintext: &[u8]) -> crate::Result<Vec<u8>> {
+        secretbox::seal(key, nonce, plaintext)
+    }
+
+    fn open(&self, key: &[u8; 32], nonce: &[u8; 24], ciphertext: &[u8]) -> crate::Result<Vec<u8>> {
+        secretbox::open(key, nonce, ciphertext)
+    }
+}
+
+/// Shared handle to the encryption backend stored in the configuration
+#[cfg(feature = "encryption")]
+#[derive(Clone)]
+pub(crate) struct BackendHandle(pub(crate) Arc<dyn EncryptionBackend>);
+
+#[cfg(feature = "encryption")]
+impl Default for BackendHandle {
+    fn default() -> Self {
+        Self(Arc::new(SecretboxBackend))
+    }
+}
+
+#[cfg(feature = "encryption")]
+impl fmt::Debug for BackendHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "EncryptionBackend")
+    }
+}
+
+#[cfg(all(feature = "encryption", not(feature = "sodiumoxide")))]
+use pure_secretbox as secretbox;
+#[cfg(all(feature = "encryption", feature = "sodiumoxide"))]
+use sodium_secretbox as secretbox;
+
+/// Pure-Rust secretbox via `crypto_secretbox` (the default)
+#[cfg(feature = "encryption")]
+#[cfg_attr(feature = "sodiumoxide", allow(dead_code))]
+mod pure_secretbox {
+    use crate::{PusherError, Result};
+    use crypto_secretbox::{
+        Key, Nonce, XSalsa20Poly1305,
+        aead::{Aead, AeadCore, KeyInit, OsRng},
+    };
+
+    pub(super) fn random_nonce() -> Result<[u8; 24]> {
+        Ok(XSalsa20Poly1305::generate_nonce(&mut OsRng).into())
+    }
+
+    pub(super) fn seal(key: &[u8; 32], nonce: &[u8; 24], plaintext: &[u8]) -> Result<Vec<u8>> {
+        XSalsa20Poly1305::new(Key::from_slice(key))
+            .encrypt(Nonce::from_slice(nonce), plaintext)
+            .map_err(|_| PusherError::Encryption {
+                message: "Encryption failed".to_string(),
+            })
+    }
+
+    pub(super) fn open(key: &[u8; 32], nonce: &[u8; 24], ciphertext: &[u8]) -> Result<Vec<u8>> {
+        XSalsa20Poly1305::new(Key::from_slice(key))
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| PusherError::Encryption {
+                message: "Decryption failed".to_string(),
+            })
+    }
+}
+
+/// libsodium secretbox via `sodiumoxide` (opt-in with the `sodiumoxide` feature)
+#[cfg(all(feature = "encryption", feature = "sodiumoxide"))]
+mod sodium_secretbox {
+    use crate::{PusherError, Result};
+    use sodiumoxide::crypto::secretbox;
+    use std::sync::OnceLock;
+
+    static SODIUM_INIT: OnceLock<bool> = OnceLock::new();
+
+    /// Initialize sodiumoxide once, reporting a failed initialization on every call
+    fn init_sodium() -> Result<()> {
+        if *SODIUM_INIT.get_or_init(|| sodiumoxide::init().is_ok()) {
+            Ok(())
+        } else {
+            Err(PusherError::Encryption {
+                message: "Failed to initialize sodiumoxide".to_string(),
+            })
+        }
+    }
+
+    pub(super) fn random_nonce() -> Result<[u8; 24]> {
+        init_sodium()?;
+        Ok(secretbox::gen_nonce().0)
+    }
+
+    pub(super) fn seal(key: &[u8; 32], nonce: &[u8; 24], plaintext: &[u8]) -> Result<Vec<u8>> {
+        init_sodium()?;
+        Ok(secretbox::seal(
+            plaintext,
+            &secretbox::Nonce(*nonce),
+            &secretbox::Key(*key),
+        ))
+    }
+
+    pub(super) fn open(key: &[u8; 32], nonce: &[u8; 24], ciphertext: &[u8]) -> Result<Vec<u8>> {
+        init_sodium()?;
+        secretbox::open(ciphertext, &secretbox::Nonce(*nonce), &secretbox::Key(*key)).map_err(
+            |_| PusherError::Encryption {
+                message: "Decryption failed".to_string(),
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_derive_shared_secret() {
+        let master_key = [7u8; 32];
+        let a = derive_shared_secret(&master_key, "private-encrypted-a");
+        let b = derive_shared_secret(&master_key, "private-encrypted-b");
+        assert_ne!(a, b);
+        assert_eq!(a, derive_shared_secret(&master_key, "private-encrypted-a"));
+        assert_eq!(
+            derive_shared_secret_base64(&master_key, "private-encrypted-a"),
+            BASE64.encode(a)
+        );
+    }
+
+    #[test]
+    fn test_key_derivation() {
+        let master_key = [7u8; 32];
+        let channel = "private-encrypted-a";
+
+        assert_eq!(
+            KeyDerivation::default().derive(&master_key, channel),
+            derive_shared_secret(&master_key, channel)
+        );
+        let hkdf = KeyDerivation::Hkdf.derive(&master_key, channel);
+        assert_ne!(hkdf, derive_shared_secret(&master_key, channel));
+        assert_ne!(
+            hkdf,
+            KeyDerivation::Hkdf.derive(&master_key, "private-encrypted-b")
+        );
+    }
+
+    #[test]
+    fn test_shared_secret_cache() {
+        let cache = SharedSecretCache::default();
+        let mut derivations = 0;
+        let mut derive = |version: u64, channel: &str| {
+            cache.get_or_derive(version, channel, || {
+                derivations += 1;
+                derive_shared_secret(&[version as u8; 32], channel)
+            })
+        };
+
+        let a = derive(0, "private-encrypted-a");
+        assert_eq!(derive(0, "private-encrypted-a"), a);
+        assert_ne!(derive(1, "private-encrypted-a"), a);
+        assert_eq!(derivations, 2);
+    }
+
+    /// XSalsa20-Poly1305 vector (key 0..32, nonce 100..124), checked against libsodium
+    #[cfg(feature = "encryption")]
+    const SECRETBOX_VECTOR: (&str, &str) = (
+        "ZGVmZ2hpamtsbW5vcHFyc3R1dnd4eXp7",
+        "bdPutfSSAKG3BToCavUTM3mb9KxJxa+O1d8Ztl/hz3RRik/IlsovOds=",
+    );
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn test_secretbox_vector() {
+        let key: [u8; 32] = std::array::from_fn(|i| i as u8);
+        let nonce: [u8; 24] = std::array::from_fn(|i| 100 + i as u8);
+        let plaintext = br#"{"message":"hello world"}"#;
+        let backend = SecretboxBackend;
+
+        assert_eq!(BASE64.encode(nonce), SECRETBOX_VECTOR.0);
+        let sealed = backend.seal(&key, &nonce, plaintext).unwrap();
+        assert_eq!(BASE64.encode(&sealed), SECRETBOX_VECTOR.1);
+        assert_eq!(backend.open(&key, &nonce, &sealed).unwrap(), plaintext);
+        assert!(backend.open(&[0; 32], &nonce, &sealed).is_err());
+    }
+
+    #[cfg(all(feature = "encryption", feature = "sodiumoxide"))]
+    #[test]
+    fn test_secretbox_backends_interoperate() {
+        let key = [7u8; 32];
+        let nonce = pure_secretbox::random_nonce().unwrap();
+        let plaintext = b"cross-backend";
+
+        let pure = pure_secretbox::seal(&key, &nonce, plaintext).unwrap();
+        let sodium = sodium_secretbox::seal(&key, &nonce, plaintext).unwrap();
+        assert_eq!(pure, sodium);
+        assert_eq!(
+            sodium_secretbox::open(&key, &nonce, &pure).unwrap(),
+            plaintext
+        );
+        assert_eq!(
+            pure_secretbox::open(&key, &nonce, &sodium).unwrap(),
+            plaintext
+        );
+    }
+}
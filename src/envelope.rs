@@ -0,0 +1,123 @@
+//! Standard metadata envelope for event payloads
+//!
+//! Wrapping payloads in an [`Envelope`] gives every producer and consumer the same JSON shape:
+//!
+//! ```json
+//! {"event_id": "3f0c…", "emitted_at": 1700000000000, "producer": "orders-service", "data": {…}}
+//! ```
+
+use crate::events::{EventData, PusherEvent};
+use crate::json;
+use crate::{PusherError, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A payload with its event ID, emission time, and producer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Envelope<T> {
+    /// Unique ID, a UUIDv4 unless set explicitly
+    pub event_id: String,
+    /// Emission time in milliseconds since the Unix epoch
+    pub emitted_at: u64,
+    /// Name of the service that emitted the event
+    pub producer: String,
+    pub data: T,
+}
+
+impl<T> Envelope<T> {
+    /// Wraps `data` with a fresh event ID and the current time
+    pub fn new(producer: impl Into<String>, data: T) -> Self {
+        let emitted_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default();
+        Self {
+            event_id: uuid::Uuid::new_v4().to_string(),
+            emitted_at,
+            producer: producer.into(),
+            data,
+        }
+    }
+
+    /// Sets the event ID, e.g. to reuse an outbox row ID
+    pub fn with_event_id(mut self, event_id: impl Into<String>) -> Self {
+        self.event_id = event_id.into();
+        self
+    }
+
+    /// Sets the emission time in milliseconds since the Unix epoch
+    pub fn with_emitted_at(mut self, emitted_at: u64) -> Self {
+        self.emitted_at = emitted_at;
+        self
+    }
+}
+
+impl<T: Serialize> Envelope<T> {
+    /// Serializes the envelope as event data
+    pub fn to_event_data(&self) -> Result<EventData> {
+        Ok(EventData::Json(json::to_value(self)?))
+    }
+}
+
+impl<T: DeserializeOwned> Envelope<T> {
+    /// Parses an envelope from received event data
+    pub fn parse(data: &str) -> Result<Self> {
+        json::from_str(data).map_err(|e| PusherError::Validation {
+            message: format!("Invalid event envelope: {}", e),
+        })
+    }
+}
+
+/// An envelope is triggered under the name of the event it wraps
+impl<E: PusherEvent> PusherEvent for Envelope<E> {
+    const NAME: &'static str = E::NAME;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json::json;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct OrderUpdated {
+        id: u64,
+    }
+
+    impl PusherEvent for OrderUpdated {
+        const NAME: &'static str = "order-updated";
+    }
+
+    #[test]
+    fn test_envelope_round_trip() {
+        let envelope = Envelope::new("orders-service", OrderUpdated { id: 42 })
+            .with_event_id("evt-1")
+            .with_emitted_at(1_700_000_000_000);
+        let data = envelope.to_event_data().unwrap();
+        assert_eq!(
+            data.as_json().unwrap(),
+            json!({
+                "event_id": "evt-1",
+                "emitted_at": 1_700_000_000_000u64,
+                "producer": "orders-service",
+                "data": {"id": 42},
+            })
+        );
+
+        let parsed = Envelope::<OrderUpdated>::parse(&data.to_string()).unwrap();
+        assert_eq!(parsed, envelope);
+        assert_eq!(
+            <Envelope<OrderUpdated> as PusherEvent>::NAME,
+            "order-updated"
+        );
+        assert!(Envelope::<OrderUpdated>::parse(r#"{"id": 42}"#).is_err());
+    }
+
+    #[test]
+    fn test_new_sets_metadata() {
+        let a = Envelope::new("svc", ());
+        let b = Envelope::new("svc", ());
+        assert_ne!(a.event_id, b.event_id);
+        assert!(a.emitted_at > 0);
+    }
+}
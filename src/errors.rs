@@ -1,3 +1,6 @@
+use crate::ratelimit::RateLimit;
+use http::HeaderMap;
+use std::time::{Duration, SystemTime};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -14,23 +17,191 @@ pub enum PusherError {
     #[error("Validation error: {message}")]
     Validation { message: String },
 
+    #[error("Validation error: Batch event {}: {message}", .event.index)]
+    InvalidBatchEvent {
+        message: String,
+        event: RejectedBatchEvent,
+    },
+
+    #[error("Batch error: {message} (events {failed_indices:?})")]
+    Batch {
+        message: String,
+        failed_indices: Vec<usize>,
+    },
+
+    #[error("Request cancelled: {message}")]
+    Cancelled { message: String },
+
+    #[error("Timeout: {message}")]
+    Timeout { message: String },
+
     #[error("Encryption error: {message}")]
     Encryption { message: String },
 
     #[error("JSON error: {0}")]
-    Json(#[from] sonic_rs::Error),
+    Json(#[from] crate::json::Error),
 
     #[error("HTTP error: {0}")]
-    Http(#[from] reqwest::Error),
+    Http(#[from] HttpError),
+}
+
+/// Failure to get a response from the HTTP transport
+///
+/// Built from `reqwest::Error` with the request URL stripped, since it carries the auth
+/// signature. Custom transports create one with [`HttpError::new`].
+#[derive(Debug)]
+pub struct HttpError {
+    message: String,
+    connect: bool,
+    timeout: bool,
+    source: HttpErrorSource,
+}
+
+#[derive(Debug)]
+enum HttpErrorSource {
+    None,
+    Boxed(Box<dyn std::error::Error + Send + Sync>),
+    // Kept whole so its causes stay reachable; its message is already in `message`
+    #[cfg(feature = "reqwest")]
+    Reqwest(reqwest::Error),
+}
+
+impl HttpError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            connect: false,
+            timeout: false,
+            source: HttpErrorSource::None,
+        }
+    }
+
+    /// Marks the failure as happening while connecting, so nothing was sent
+    pub fn connect(mut self) -> Self {
+        self.connect = true;
+        self
+    }
+
+    /// Marks the failure as a timeout
+    pub fn timeout(mut self) -> Self {
+        self.timeout = true;
+        self
+    }
+
+    /// Attaches the underlying error
+    pub fn with_source(
+        mut self,
+        source: impl Into<Box<dyn std::error::Error + Send + Sync>>,
+    ) -> Self {
+        self.source = HttpErrorSource::Boxed(source.into());
+        self
+    }
+
+    /// Whether the connection could not be established, so the request was never sent
+    pub fn is_connect(&self) -> bool {
+        self.connect
+    }
+
+    pub fn is_timeout(&self) -> bool {
+        self.timeout
+    }
+}
+
+impl std::fmt::Display for HttpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for HttpError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.source {
+            HttpErrorSource::None => None,
+            HttpErrorSource::Boxed(source) => Some(source.as_ref()),
+            #[cfg(feature = "reqwest")]
+            HttpErrorSource::Reqwest(error) => std::error::Error::source(error),
+        }
+    }
+}
+
+#[cfg(feature = "reqwest")]
+impl From<reqwest::Error> for HttpError {
+    fn from(error: reqwest::Error) -> Self {
+        let error = error.without_url();
+        Self {
+            message: error.to_string(),
+            connect: error.is_connect(),
+            timeout: error.is_timeout(),
+            source: HttpErrorSource::Reqwest(error),
+        }
+    }
+}
+
+#[cfg(feature = "reqwest")]
+impl From<reqwest::Error> for PusherError {
+    fn from(error: reqwest::Error) -> Self {
+        PusherError::Http(error.into())
+    }
 }
 
 #[derive(Error, Debug)]
 #[error("HTTP request failed")]
+#[non_exhaustive]
 pub struct RequestError {
     pub message: String,
+    /// Request URL with the auth key and signature redacted
     pub url: String,
+    /// Host the request was sent to
+    pub host: String,
+    /// Request path, without the query string
+    pub path: String,
+    /// Full signed URL, only set when enabled with `ConfigBuilder::include_signed_url_in_errors`
+    pub signed_url: Option<String>,
     pub status: Option<u16>,
     pub body: Option<String>,
+    /// Every attempt made before the request was given up on
+    pub attempts: Vec<AttemptInfo>,
+    /// Total time spent across all attempts and backoff
+    pub elapsed: Duration,
+    /// ID sent in the `X-Request-Id` header of every attempt
+    pub request_id: Option<String>,
+    /// Headers of the last response, if one was received
+    pub headers: Option<Box<HeaderMap>>,
+    /// Rate-limit headers of the last response
+    pub rate_limit: Option<RateLimit>,
+    /// Batch event the API named as the reason it rejected a batch
+    pub rejected_event: Option<RejectedBatchEvent>,
+    /// Transport error of the last attempt, if no response was received
+    pub source: Option<Box<HttpError>>,
+}
+
+/// Event a rejected batch request was blamed on, identified from the `index` field of a JSON
+/// error response, or found invalid before the batch was sent
+///
+/// Only reported when the batch was sent as a single request, which the API rejects as a whole,
+/// so none of its events were delivered; drop or repair this event and resend the others. Batches
+/// split on 413 report undelivered events through [`PusherError::Batch`] instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RejectedBatchEvent {
+    /// Position of the event in the batch passed to `trigger_batch`
+    pub index: usize,
+    pub channel: String,
+    pub name: String,
+}
+
+/// Outcome of a single HTTP attempt
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttemptInfo {
+    /// 1-based attempt number
+    pub attempt: u32,
+    /// Response status, if a response was received
+    pub status: Option<u16>,
+    /// Network error message, if no response was received
+    pub error: Option<String>,
+    /// Backoff slept after this attempt, if it was retried
+    pub backoff: Option<Duration>,
+    /// When the attempt was started
+    pub timestamp: SystemTime,
 }
 
 impl RequestError {
@@ -40,13 +211,67 @@ impl RequestError {
         status: Option<u16>,
         body: Option<String>,
     ) -> Self {
+        let url = url.into();
+        let (host, path) = match url::Url::parse(&url) {
+            Ok(parsed) => (
+                parsed.host_str().unwrap_or_default().to_string(),
+                parsed.path().to_string(),
+            ),
+            Err(_) => (
+                String::new(),
+                url.split('?').next().unwrap_or_default().to_string(),
+            ),
+        };
+
         Self {
             message: message.into(),
-            url: url.into(),
+            url: crate::util::redact_signed_url(&url),
+            host,
+            path,
+            signed_url: None,
             status,
             body,
+            attempts: Vec::new(),
+            elapsed: Duration::ZERO,
+            request_id: None,
+            headers: None,
+            rate_limit: None,
+            rejected_event: None,
+            source: None,
         }
     }
+
+    /// Attaches the transport error of the last attempt
+    pub fn with_source(mut self, source: HttpError) -> Self {
+        self.source = Some(Box::new(source));
+        self
+    }
+
+    /// Attaches the full signed URL for debugging
+    pub fn with_signed_url(mut self, signed_url: impl Into<String>) -> Self {
+        self.signed_url = Some(signed_url.into());
+        self
+    }
+
+    /// Attaches the client-generated request ID
+    pub fn with_request_id(mut self, request_id: impl Into<String>) -> Self {
+        self.request_id = Some(request_id.into());
+        self
+    }
+
+    /// Attaches the headers of the response that failed, including its rate-limit state
+    pub fn with_headers(mut self, headers: HeaderMap) -> Self {
+        self.rate_limit = RateLimit::from_headers(&headers);
+        self.headers = Some(Box::new(headers));
+        self
+    }
+
+    /// Attaches the attempt history and total elapsed time
+    pub fn with_attempts(mut self, attempts: Vec<AttemptInfo>, elapsed: Duration) -> Self {
+        self.attempts = attempts;
+        self.elapsed = elapsed;
+        self
+    }
 }
 
 #[derive(Error, Debug)]
@@ -73,3 +298,268 @@ impl WebhookError {
         }
     }
 }
+
+/// Reason a webhook failed validation
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum WebhookValidationError {
+    #[error("Invalid webhook content type: {0:?}")]
+    InvalidContentType(Option<String>),
+
+    #[error("Invalid webhook body")]
+    InvalidBody,
+
+    #[error("Missing X-Pusher-Key header")]
+    MissingKey,
+
+    #[error("Missing X-Pusher-Signature header")]
+    MissingSignature,
+
+    #[error("Unknown webhook key: {0}")]
+    UnknownKey(String),
+
+    #[error("Webhook signature mismatch")]
+    SignatureMismatch,
+
+    #[error("Webhook timestamp {time_ms} is outside the allowed tolerance")]
+    OutsideTolerance { time_ms: i64 },
+}
+
+/// Reason [`Pusher::verify_credentials`](crate::Pusher::verify_credentials) failed
+#[derive(Error, Debug)]
+pub enum CredentialError {
+    /// The API rejected the app ID, key, or secret (HTTP 401/403)
+    #[error("Invalid Pusher credentials (HTTP {status}): {message}")]
+    InvalidCredentials { status: u16, message: String },
+
+    /// The credentials could not be checked, e.g. the API was unreachable or returned 5xx
+    #[error("Could not verify Pusher credentials: {0}")]
+    Unavailable(#[source] PusherError),
+}
+
+impl From<PusherError> for CredentialError {
+    fn from(error: PusherError) -> Self {
+        match error.status() {
+            Some(status @ (401 | 403)) => {
+                let message = match &error {
+                    PusherError::Request(e) => e.body.clone().unwrap_or_else(|| e.message.clone()),
+                    other => other.to_string(),
+                };
+                CredentialError::InvalidCredentials { status, message }
+            }
+            _ => CredentialError::Unavailable(error),
+        }
+    }
+}
+
+/// Reason an incoming REST API request failed signature verification
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum RequestVerificationError {
+    #[error("Missing query parameter: {0}")]
+    MissingParameter(&'static str),
+
+    #[error("Unknown auth_key: {0}")]
+    UnknownKey(String),
+
+    #[error("Unsupported auth_version: {0}")]
+    UnsupportedVersion(String),
+
+    #[error("Invalid auth_timestamp")]
+    InvalidTimestamp,
+
+    #[error("auth_timestamp {timestamp} is outside the allowed tolerance")]
+    TimestampOutsideTolerance { timestamp: u64 },
+
+    #[error("Invalid auth_expires")]
+    InvalidExpiry,
+
+    #[error("Request expired at {expires}")]
+    Expired { expires: u64 },
+
+    #[error("auth_expires {expires} is further in the future than allowed")]
+    ExpiryTooFar { expires: u64 },
+
+    #[error("body_md5 does not match the request body")]
+    BodyMd5Mismatch,
+
+    #[error("auth_signature mismatch")]
+    SignatureMismatch,
+}
+
+/// Reason a [delegation token](crate::delegation) was rejected
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum DelegationError {
+    #[error("Malformed delegation token")]
+    Malformed,
+
+    #[error("Unsupported delegation token algorithm: {0}")]
+    UnsupportedAlgorithm(String),
+
+    #[error("Unknown delegation token key: {0}")]
+    UnknownKey(String),
+
+    #[error("Delegation token signature mismatch")]
+    SignatureMismatch,
+
+    #[error("Delegation token issued in the future at {issued_at}")]
+    NotYetValid { issued_at: u64 },
+
+    #[error("Delegation token expired at {expires_at}")]
+    Expired { expires_at: u64 },
+
+    #[error("Delegation token is bound to a different socket")]
+    SocketMismatch,
+
+    #[error("Delegation token does not grant channel {0}")]
+    ChannelNotGranted(String),
+}
+
+impl PusherError {
+    /// Gets the HTTP status code of the failed request, if one was received
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            PusherError::Request(e) => e.status,
+            _ => None,
+        }
+    }
+
+    /// Checks if retrying the same operation later may succeed
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            PusherError::Request(e) => match e.status {
+                Some(status) => status >= 500 || status == 408 || status == 429,
+                // No response was received
+                None => match &e.source {
+                    Some(source) => source.is_timeout() || source.is_connect(),
+                    None => !e.attempts.is_empty(),
+                },
+            },
+            PusherError::Http(e) => e.is_timeout() || e.is_connect(),
+            PusherError::Timeout { .. } => true,
+            _ => false,
+        }
+    }
+
+    /// Gets the rate-limit headers of the failed request's response, if any were sent
+    pub fn rate_limit(&self) -> Option<RateLimit> {
+        match self {
+            PusherError::Request(e) => e.rate_limit,
+            _ => None,
+        }
+    }
+
+    /// Gets the batch event the API named when rejecting a batch, or that failed local
+    /// validation, if it could be identified
+    pub fn rejected_batch_event(&self) -> Option<&RejectedBatchEvent> {
+        match self {
+            PusherError::Request(e) => e.rejected_event.as_ref(),
+            PusherError::InvalidBatchEvent { event, .. } => Some(event),
+            _ => None,
+        }
+    }
+
+    /// Checks if the request was rejected by rate limiting (HTTP 429)
+    pub fn is_rate_limited(&self) -> bool {
+        self.status() == Some(429)
+    }
+
+    /// Checks if the request was rejected because of invalid credentials (HTTP 401/403)
+    pub fn is_auth_error(&self) -> bool {
+        matches!(self.status(), Some(401) | Some(403))
+    }
+
+    /// Checks if the error was caught locally before any request was sent
+    pub fn is_validation_error(&self) -> bool {
+        matches!(
+            self,
+            PusherError::Validation { .. }
+                | PusherError::InvalidBatchEvent { .. }
+                | PusherError::Config { .. }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request_error(status: Option<u16>) -> PusherError {
+        RequestError::new("failed", "/apps/1/events", status, None).into()
+    }
+
+    #[test]
+    fn test_request_error_url_redaction() {
+        let err = RequestError::new(
+            "HTTP 500",
+            "https://api.pusherapp.com/apps/1/events?auth_key=key&auth_signature=abc",
+            Some(500),
+            None,
+        );
+
+        assert_eq!(err.host, "api.pusherapp.com");
+        assert_eq!(err.path, "/apps/1/events");
+        assert!(!err.url.contains("abc"));
+        assert!(err.url.contains("auth_key=[REDACTED]"));
+        assert_eq!(err.signed_url, None);
+    }
+
+    #[test]
+    fn test_error_classification() {
+        assert!(request_error(Some(503)).is_retryable());
+        assert!(request_error(Some(429)).is_retryable());
+        assert!(request_error(Some(429)).is_rate_limited());
+        assert!(!request_error(Some(400)).is_retryable());
+
+        assert!(request_error(Some(401)).is_auth_error());
+        assert!(request_error(Some(403)).is_auth_error());
+        assert!(!request_error(Some(500)).is_auth_error());
+
+        let validation = PusherError::Validation {
+            message: "bad".to_string(),
+        };
+        assert!(validation.is_validation_error());
+        assert!(!validation.is_retryable());
+        assert_eq!(validation.status(), None);
+
+        let timeout = PusherError::Timeout {
+            message: "slow".to_string(),
+        };
+        assert!(timeout.is_retryable());
+    }
+
+    #[test]
+    fn test_credential_error_classification() {
+        let rejected = PusherError::from(RequestError::new(
+            "failed",
+            "/apps/1/channels",
+            Some(401),
+            Some("Invalid signature".to_string()),
+        ));
+        let CredentialError::InvalidCredentials { status, message } = rejected.into() else {
+            panic!("expected invalid credentials");
+        };
+        assert_eq!((status, message.as_str()), (401, "Invalid signature"));
+        assert!(matches!(
+            CredentialError::from(request_error(Some(503))),
+            CredentialError::Unavailable(_)
+        ));
+        assert!(matches!(
+            CredentialError::from(request_error(None)),
+            CredentialError::Unavailable(_)
+        ));
+    }
+
+    #[test]
+    fn test_rate_limit_from_error_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-remaining", "0".parse().unwrap());
+        headers.insert("retry-after", "5".parse().unwrap());
+        let error = PusherError::from(
+            RequestError::new("HTTP 429", "/apps/1/events", Some(429), None).with_headers(headers),
+        );
+
+        let rate_limit = error.rate_limit().unwrap();
+        assert!(rate_limit.is_exhausted());
+        assert_eq!(rate_limit.retry_after, Some(Duration::from_secs(5)));
+        assert_eq!(request_error(Some(429)).rate_limit(), None);
+    }
+}
@@ -1,30 +1,34 @@
-use crate::{Channel, Pusher, PusherError, Result};
+use crate::channel::MAX_TRIGGER_CHANNELS;
+use crate::config::DEFAULT_MAX_EVENT_PAYLOAD_BYTES;
+use crate::errors::RejectedBatchEvent;
+use crate::json::JsonValueTrait;
+use crate::json::{self, Value, json};
+use crate::pusher::RequestOptions;
+use crate::transport::Response;
+use crate::{Channel, Pusher, PusherError, Result, RetryMode, util};
 use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
 use serde::{Deserialize, Serialize};
-use sonic_rs::{Value, json};
+use std::borrow::Borrow;
 use std::collections::HashMap;
 use std::fmt;
+#[cfg(feature = "encryption")]
+use zeroize::Zeroizing;
 
-#[cfg(all(feature = "encryption", feature = "sodiumoxide"))]
-use std::sync::Once;
-
-#[cfg(all(feature = "encryption", feature = "sodiumoxide"))]
-static SODIUM_INIT: Once = Once::new();
+/// Maximum number of events accepted by `/batch_events`
+const MAX_BATCH_EVENTS: usize = 10;
 
-/// Initialize sodiumoxide once
-#[cfg(all(feature = "encryption", feature = "sodiumoxide"))]
-fn init_sodium() -> Result<()> {
-    SODIUM_INIT.call_once(|| {
-        sodiumoxide::init().expect("Failed to initialize sodiumoxide");
-    });
-    Ok(())
-}
+/// Number of encrypted events in a batch from which they are encrypted in parallel
+#[cfg(feature = "encryption")]
+const PARALLEL_ENCRYPTION_THRESHOLD: usize = 4;
 
-/// Event data that can be either a string or JSON
+/// Event data that can be a string, JSON, or binary
+///
+/// Binary data is sent as a base64 string; [`EventData::to_bytes`] decodes it again.
 #[derive(Debug, Clone, PartialEq)]
 pub enum EventData {
     String(String),
     Json(Value),
+    Binary(Vec<u8>),
 }
 
 impl EventData {
@@ -38,21 +42,45 @@ impl EventData {
         EventData::Json(value)
     }
 
+    /// Creates event data from bytes, sent base64-encoded
+    pub fn from_bytes(bytes: impl Into<Vec<u8>>) -> Self {
+        EventData::Binary(bytes.into())
+    }
+
     /// Converts the event data to a string for transmission
     pub fn to_string(&self) -> String {
         match self {
             EventData::String(s) => s.clone(),
-            EventData::Json(v) => sonic_rs::to_string(v).unwrap_or_default(),
+            EventData::Json(v) => json::to_string(v).unwrap_or_default(),
+            EventData::Binary(b) => BASE64.encode(b),
         }
     }
 
-    /// Gets the event data as a JSON value
+    /// Gets the event data as a JSON value (binary data becomes a base64 string)
     pub fn as_json(&self) -> Result<Value> {
         match self {
-            EventData::String(s) => sonic_rs::from_str(s).map_err(|e| PusherError::Json(e)),
+            EventData::String(s) => json::from_str(s).map_err(|e| PusherError::Json(e)),
             EventData::Json(v) => Ok(v.clone()),
+            EventData::Binary(b) => Ok(json!(BASE64.encode(b))),
         }
     }
+
+    /// Gets binary event data, decoding base64 received as a string
+    ///
+    /// Use it on data received from webhooks or client events that was sent as
+    /// [`EventData::Binary`].
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let encoded = match self {
+            EventData::Binary(b) => return Ok(b.clone()),
+            EventData::String(s) => s.as_str(),
+            EventData::Json(v) => v.as_str().ok_or_else(|| PusherError::Validation {
+                message: "Event data is not a base64 string".to_string(),
+            })?,
+        };
+        BASE64.decode(encoded).map_err(|e| PusherError::Validation {
+            message: format!("Event data is not valid base64: {}", e),
+        })
+    }
 }
 
 impl fmt::Display for EventData {
@@ -79,8 +107,23 @@ impl From<Value> for EventData {
     }
 }
 
+impl From<Vec<u8>> for EventData {
+    fn from(b: Vec<u8>) -> Self {
+        EventData::Binary(b)
+    }
+}
+
+impl From<&[u8]> for EventData {
+    fn from(b: &[u8]) -> Self {
+        EventData::Binary(b.to_vec())
+    }
+}
+
 /// Event data for triggering
-#[derive(Debug, Serialize)]
+///
+/// Serializes to the `/events` request body and deserializes from it, so events can be stored
+/// and replayed with [`Pusher::trigger_prepared`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Event {
     pub name: String,
     pub data: String,
@@ -93,8 +136,163 @@ pub struct Event {
     pub tags: Option<HashMap<String, String>>,
 }
 
+impl Event {
+    /// Creates a new Event builder
+    pub fn builder() -> EventBuilder {
+        EventBuilder::default()
+    }
+
+    /// Checks the event against the API limits
+    ///
+    /// Validates the name length, channel names and count, socket ID and data size. Events on
+    /// encrypted channels are rejected: their data must be encrypted per channel, so trigger
+    /// them with [`Pusher::trigger_encrypted`] instead.
+    pub fn validate(&self, max_payload_bytes: usize) -> Result<()> {
+        if self.name.is_empty() || self.name.len() > 200 {
+            return Err(PusherError::Validation {
+                message: format!(
+                    "Invalid event name: '{}' (must be 1-200 characters)",
+                    self.name
+                ),
+            });
+        }
+        if self.channels.is_empty() {
+            return Err(PusherError::Validation {
+                message: "Must specify at least one channel".to_string(),
+            });
+        }
+        if self.channels.len() > MAX_TRIGGER_CHANNELS {
+            return Err(PusherError::Validation {
+                message: format!(
+                    "Can't trigger to more than {} channels (got {})",
+                    MAX_TRIGGER_CHANNELS,
+                    self.channels.len()
+                ),
+            });
+        }
+        for name in &self.channels {
+            if Channel::from_string(name)?.is_encrypted() {
+                return Err(PusherError::Validation {
+                    message: format!(
+                        "Event on encrypted channel '{}' must be triggered with trigger_encrypted",
+                        name
+                    ),
+                });
+            }
+        }
+        if let Some(ref socket_id) = self.socket_id {
+            util::validate_socket_id(socket_id)?;
+        }
+        check_payload_size(&self.data, max_payload_bytes)
+    }
+}
+
+/// Builder for Event
+#[derive(Debug, Default)]
+pub struct EventBuilder {
+    name: Option<String>,
+    channels: Vec<String>,
+    data: Option<EventData>,
+    socket_id: Option<String>,
+    info: Option<String>,
+    tags: Option<HashMap<String, String>>,
+    max_payload_bytes: Option<usize>,
+}
+
+impl EventBuilder {
+    /// Sets the event name
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Adds a channel to trigger the event on
+    pub fn channel(mut self, channel: impl Borrow<Channel>) -> Self {
+        self.channels.push(channel.borrow().full_name());
+        self
+    }
+
+    /// Adds several channels to trigger the event on
+    pub fn channels(self, channels: impl IntoIterator<Item = impl Borrow<Channel>>) -> Self {
+        channels
+            .into_iter()
+            .fold(self, |builder, channel| builder.channel(channel))
+    }
+
+    /// Sets the event data
+    pub fn data(mut self, data: impl Into<EventData>) -> Self {
+        self.data = Some(data.into());
+        self
+    }
+
+    /// Sets the socket ID to exclude
+    pub fn socket_id(mut self, socket_id: impl Into<String>) -> Self {
+        self.socket_id = Some(socket_id.into());
+        self
+    }
+
+    /// Sets the info parameter
+    pub fn info(mut self, info: impl Into<String>) -> Self {
+        self.info = Some(info.into());
+        self
+    }
+
+    /// Sets the tags for tag filtering
+    pub fn tags(mut self, tags: HashMap<String, String>) -> Self {
+        self.tags = Some(tags);
+        self
+    }
+
+    /// Sets the data size limit (default: [`DEFAULT_MAX_EVENT_PAYLOAD_BYTES`])
+    pub fn max_payload_bytes(mut self, max: usize) -> Self {
+        self.max_payload_bytes = Some(max);
+        self
+    }
+
+    /// Builds and validates the event
+    pub fn build(self) -> Result<Event> {
+        let event = Event {
+            name: self.name.unwrap_or_default(),
+            data: self.data.map(|d| d.to_string()).unwrap_or_default(),
+            channels: self.channels,
+            socket_id: self.socket_id,
+            info: self.info,
+            tags: self.tags,
+        };
+        event.validate(
+            self.max_payload_bytes
+                .unwrap_or(DEFAULT_MAX_EVENT_PAYLOAD_BYTES),
+        )?;
+        Ok(event)
+    }
+}
+
+/// An event type with a fixed name, serialized as the event data
+///
+/// With the `derive` feature, `#[derive(PusherEvent)]` implements it, taking the name from
+/// `#[pusher(name = "...")]` or the kebab-cased type name:
+///
+/// ```ignore
+/// #[derive(Serialize, PusherEvent)]
+/// #[pusher(name = "order-updated")]
+/// struct OrderUpdated {
+///     id: u64,
+/// }
+///
+/// pusher.trigger_event(&channel, &OrderUpdated { id: 42 }).await?;
+/// ```
+pub trait PusherEvent: Serialize {
+    /// Event name
+    const NAME: &'static str;
+
+    /// Serializes the event data
+    fn event_data(&self) -> Result<EventData> {
+        Ok(EventData::Json(json::to_value(self)?))
+    }
+}
+
 /// Batch event data
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BatchEvent {
     pub name: String,
     pub channel: String,
@@ -124,6 +322,42 @@ impl BatchEvent {
         }
     }
 
+    /// Creates a batch event by serializing `data` straight to its JSON string
+    ///
+    /// Fails if the data exceeds [`DEFAULT_MAX_EVENT_PAYLOAD_BYTES`]; use
+    /// [`BatchEvent::from_serialize_with_limit`] if your plan allows larger events.
+    pub fn from_serialize<T: Serialize + ?Sized>(
+        name: impl Into<String>,
+        channel: impl Into<String>,
+        data: &T,
+    ) -> Result<Self> {
+        Self::from_serialize_with_limit(name, channel, data, DEFAULT_MAX_EVENT_PAYLOAD_BYTES)
+    }
+
+    /// Creates a batch event by serializing `data`, failing if it exceeds `max_payload_bytes`
+    pub fn from_serialize_with_limit<T: Serialize + ?Sized>(
+        name: impl Into<String>,
+        channel: impl Into<String>,
+        data: &T,
+        max_payload_bytes: usize,
+    ) -> Result<Self> {
+        let data = json::to_string(data)?;
+        check_payload_size(&data, max_payload_bytes)?;
+        Ok(Self {
+            name: name.into(),
+            channel: channel.into(),
+            data,
+            socket_id: None,
+            info: None,
+            tags: None,
+        })
+    }
+
+    /// Creates a batch event from a typed event
+    pub fn from_event<E: PusherEvent>(channel: impl Into<String>, event: &E) -> Result<Self> {
+        Ok(Self::new(E::NAME, channel, event.event_data()?))
+    }
+
     /// Sets the socket ID to exclude
     pub fn with_socket_id(mut self, socket_id: impl Into<String>) -> Self {
         self.socket_id = Some(socket_id.into());
@@ -143,12 +377,153 @@ impl BatchEvent {
     }
 }
 
+/// Packs events into batches that respect the `/batch_events` limits
+///
+/// [`push`](Self::push) returns the current batch once the next event no longer fits, so a
+/// producer can send each full batch with [`Pusher::trigger_batch`] and call
+/// [`finish`](Self::finish) for the remainder. Sizes are of the data as given; events on
+/// encrypted channels grow when encrypted.
+#[derive(Debug, Clone)]
+pub struct BatchBuilder {
+    events: Vec<BatchEvent>,
+    data_bytes: usize,
+    max_events: usize,
+    max_payload_bytes: usize,
+    max_batch_bytes: Option<usize>,
+}
+
+impl Default for BatchBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BatchBuilder {
+    /// Creates a builder with Pusher's limits of 10 events of at most
+    /// [`DEFAULT_MAX_EVENT_PAYLOAD_BYTES`] each
+    pub fn new() -> Self {
+        Self {
+            events: Vec::new(),
+            data_bytes: 0,
+            max_events: MAX_BATCH_EVENTS,
+            max_payload_bytes: DEFAULT_MAX_EVENT_PAYLOAD_BYTES,
+            max_batch_bytes: None,
+        }
+    }
+
+    /// Sets the maximum number of events per batch, capped at 10
+    pub fn max_events(mut self, max: usize) -> Self {
+        self.max_events = max.clamp(1, MAX_BATCH_EVENTS);
+        self
+    }
+
+    /// Sets the maximum size of a single event's data
+    pub fn max_payload_bytes(mut self, max: usize) -> Self {
+        self.max_payload_bytes = max;
+        self
+    }
+
+    /// Limits the combined data size of a batch
+    pub fn max_batch_bytes(mut self, max: usize) -> Self {
+        self.max_batch_bytes = Some(max);
+        self
+    }
+
+    /// Adds an event, returning the previous batch if the event did not fit into it
+    ///
+    /// Fails without changing the builder if the event itself is invalid or too large.
+    pub fn push(&mut self, event: BatchEvent) -> Result<Option<Vec<BatchEvent>>> {
+        if event.name.is_empty() || event.name.len() > 200 {
+            return Err(PusherError::Validation {
+                message: format!(
+                    "Event name must be 1-200 characters (got {})",
+                    event.name.len()
+                ),
+            });
+        }
+        check_payload_size(&event.data, self.max_payload_bytes)?;
+        if let Some(max) = self.max_batch_bytes {
+            check_payload_size(&event.data, max)?;
+        }
+
+        let flushed = (!self.fits(&event)).then(|| self.take());
+        self.data_bytes += event.data.len();
+        self.events.push(event);
+        Ok(flushed)
+    }
+
+    /// Whether the event can be added without exceeding the limits
+    pub fn fits(&self, event: &BatchEvent) -> bool {
+        self.events.len() < self.max_events
+            && self
+                .max_batch_bytes
+                .is_none_or(|max| self.data_bytes + event.data.len() <= max)
+    }
+
+    /// Whether no further event can be added
+    pub fn is_full(&self) -> bool {
+        self.events.len() >= self.max_events
+            || self
+                .max_batch_bytes
+                .is_some_and(|max| self.data_bytes >= max)
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Combined data size of the current batch
+    pub fn data_bytes(&self) -> usize {
+        self.data_bytes
+    }
+
+    /// Removes and returns the current batch, leaving the builder empty
+    pub fn take(&mut self) -> Vec<BatchEvent> {
+        self.data_bytes = 0;
+        std::mem::take(&mut self.events)
+    }
+
+    /// Returns the last batch, or `None` if it is empty
+    pub fn finish(mut self) -> Option<Vec<BatchEvent>> {
+        let batch = self.take();
+        (!batch.is_empty()).then_some(batch)
+    }
+}
+
 /// Parameters for triggering events
-#[derive(Debug, Clone, Default)]
+///
+/// The idempotency key, trace context and retry mode of the request options are serialized
+/// and compared, so an outbox relay resends with the same key; the deadline and cancellation
+/// token apply to a single call and are neither.
+///
+/// Build it with [`TriggerParams::builder`]; new fields may be added in minor releases.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[non_exhaustive]
 pub struct TriggerParams {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub socket_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub info: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub tags: Option<HashMap<String, String>>,
+    #[serde(flatten)]
+    pub request_options: RequestOptions,
+}
+
+impl PartialEq for TriggerParams {
+    fn eq(&self, other: &Self) -> bool {
+        let (options, other_options) = (&self.request_options, &other.request_options);
+        self.socket_id == other.socket_id
+            && self.info == other.info
+            && self.tags == other.tags
+            && options.idempotency_key == other_options.idempotency_key
+            && options.trace_context == other_options.trace_context
+            && options.retry_mode == other_options.retry_mode
+    }
 }
 
 impl TriggerParams {
@@ -164,6 +539,7 @@ pub struct TriggerParamsBuilder {
     socket_id: Option<String>,
     info: Option<String>,
     tags: Option<HashMap<String, String>>,
+    request_options: RequestOptions,
 }
 
 impl TriggerParamsBuilder {
@@ -185,142 +561,245 @@ impl TriggerParamsBuilder {
         self
     }
 
+    /// Sets an absolute deadline for the request, including retries
+    pub fn deadline(mut self, deadline: std::time::Instant) -> Self {
+        self.request_options.deadline = Some(deadline);
+        self
+    }
+
+    /// Sets a token that cancels the request when triggered
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub fn cancellation_token(mut self, token: tokio_util::sync::CancellationToken) -> Self {
+        self.request_options.cancellation_token = Some(token);
+        self
+    }
+
+    /// Sets the idempotency key sent with the request
+    pub fn idempotency_key(mut self, key: impl Into<String>) -> Self {
+        self.request_options.idempotency_key = Some(key.into());
+        self
+    }
+
+    /// Overrides the configured retry mode for this request
+    pub fn retry_mode(mut self, mode: RetryMode) -> Self {
+        self.request_options.retry_mode = Some(mode);
+        self
+    }
+
+    /// Sets the W3C trace context injected into the request
+    pub fn trace_context(mut self, context: crate::trace::TraceContext) -> Self {
+        self.request_options.trace_context = Some(context);
+        self
+    }
+
     /// Builds the TriggerParams
     pub fn build(self) -> TriggerParams {
         TriggerParams {
             socket_id: self.socket_id,
             info: self.info,
             tags: self.tags,
+            request_options: self.request_options,
         }
     }
 }
 
-/// Encrypts data for encrypted channels
-#[cfg(feature = "encryption")]
-fn encrypt(pusher: &Pusher, channel: &str, data: &EventData) -> Result<String> {
-    #[cfg(feature = "sodiumoxide")]
-    {
-        encrypt_sodiumoxide(pusher, channel, data)
+/// Validates an event name's length and, if configured, rejects `client-` names
+pub(crate) fn validate_event_name(pusher: &Pusher, name: &str) -> Result<()> {
+    if name.len() > 200 {
+        return Err(PusherError::Validation {
+            message: format!("Event name too long: '{}' (max 200 characters)", name),
+        });
     }
+    if pusher.config().reject_client_event_names() && name.starts_with("client-") {
+        return Err(PusherError::Validation {
+            message: format!(
+                "Event name '{}' is reserved for client events (starts with 'client-')",
+                name
+            ),
+        });
+    }
+    Ok(())
+}
 
-    #[cfg(not(feature = "sodiumoxide"))]
-    {
-        encrypt_pure_rust(pusher, channel, data)
+/// Validates the name, channel, and socket ID of a batch event, returning whether its channel
+/// is encrypted
+fn validate_batch_event(pusher: &Pusher, event: &BatchEvent) -> Result<bool> {
+    if event.name.is_empty() {
+        return Err(PusherError::Validation {
+            message: "Event name cannot be empty".to_string(),
+        });
+    }
+    validate_event_name(pusher, &event.name)?;
+    if let Some(ref socket_id) = event.socket_id {
+        util::validate_socket_id(socket_id)?;
     }
+    Ok(Channel::from_string(&event.channel)?.is_encrypted())
 }
 
-/// Encrypts data using sodiumoxide
-#[cfg(all(feature = "encryption", feature = "sodiumoxide"))]
-fn encrypt_sodiumoxide(pusher: &Pusher, channel: &str, data: &EventData) -> Result<String> {
-    init_sodium()?;
+/// Attaches the batch event that caused a validation error to it
+fn at_batch_event(index: usize, event: &BatchEvent) -> impl Fn(PusherError) -> PusherError {
+    move |error| match error {
+        PusherError::Validation { message } => PusherError::InvalidBatchEvent {
+            message,
+            event: RejectedBatchEvent {
+                index,
+                channel: event.channel.clone(),
+                name: event.name.clone(),
+            },
+        },
+        other => other,
+    }
+}
 
-    // Ensure master key is present
-    let _master_key =
-        pusher
-            .config()
-            .encryption_master_key()
-            .ok_or_else(|| PusherError::Encryption {
-                message: "Set encryptionMasterKey before triggering events on encrypted channels"
-                    .to_string(),
-            })?;
-
-    // Generate a random nonce
-    let nonce_bytes =
-        sodiumoxide::randombytes::randombytes(sodiumoxide::crypto::secretbox::NONCEBYTES);
-    let nonce =
-        sodiumoxide::crypto::secretbox::Nonce::from_slice(&nonce_bytes).ok_or_else(|| {
-            PusherError::Encryption {
-                message: "Failed to create nonce from random bytes".to_string(),
-            }
-        })?;
+/// Validates that event data fits within the configured payload limit
+fn validate_payload_size(pusher: &Pusher, data: &str) -> Result<()> {
+    check_payload_size(data, pusher.config().max_event_payload_bytes())
+}
 
-    // Get channel shared secret
-    let shared_secret_bytes = pusher.channel_shared_secret(channel)?;
+/// Validates that event data fits within `max` bytes
+fn check_payload_size(data: &str, max: usize) -> Result<()> {
+    if data.len() > max {
+        return Err(PusherError::Validation {
+            message: format!(
+                "Event data too large: {} bytes (max {} bytes)",
+                data.len(),
+                max
+            ),
+        });
+    }
+    Ok(())
+}
 
-    // Convert to cryptographic Key type
-    let key =
-        sodiumoxide::crypto::secretbox::Key::from_slice(&shared_secret_bytes).ok_or_else(|| {
-            PusherError::Encryption {
-                message: format!(
-                    "Channel shared secret must be {} bytes long, but was {} bytes.",
-                    sodiumoxide::crypto::secretbox::KEYBYTES,
-                    shared_secret_bytes.len()
-                ),
-            }
-        })?;
+/// Encrypts data for encrypted channels
+#[cfg(feature = "encryption")]
+pub(crate) fn encrypt(pusher: &Pusher, channel: &str, data: &EventData) -> Result<String> {
+    // Ensure master key is present
+    if !pusher.has_encryption_master_key() {
+        return Err(PusherError::Encryption {
+            message: "Set encryptionMasterKey before triggering events on encrypted channels"
+                .to_string(),
+        });
+    }
 
-    // Get data as bytes
-    let data_string = data.to_string();
-    let data_bytes = data_string.as_bytes();
+    let plaintext = Zeroizing::new(data.to_string());
+    encrypt_plaintext(pusher, channel, plaintext.as_bytes())
+}
 
-    // Encrypt the data
-    let ciphertext = sodiumoxide::crypto::secretbox::seal(data_bytes, &nonce, &key);
+/// Seals the plaintext for the channel and returns the encrypted payload JSON
+#[cfg(feature = "encryption")]
+fn encrypt_plaintext(pusher: &Pusher, channel: &str, plaintext: &[u8]) -> Result<String> {
+    let backend = pusher.config().encryption_backend();
+    let shared_secret_bytes = pusher.channel_shared_secret(channel)?;
+    let nonce = backend.generate_nonce()?;
+    let ciphertext = backend.seal(&shared_secret_bytes, &nonce, plaintext)?;
 
     // Return encrypted payload as JSON string
-    let encrypted_payload = json!({
-        "nonce": BASE64.encode(nonce.as_ref()),
-        "ciphertext": BASE64.encode(&ciphertext),
-    });
+    let encrypted_payload = EncryptedPayload {
+        nonce: BASE64.encode(nonce),
+        ciphertext: BASE64.encode(&ciphertext),
+    };
 
-    Ok(sonic_rs::to_string(&encrypted_payload)?)
+    Ok(json::to_string(&encrypted_payload)?)
 }
 
-/// Encrypts data using pure Rust crypto libraries
-#[cfg(all(feature = "encryption", not(feature = "sodiumoxide")))]
-fn encrypt_pure_rust(pusher: &Pusher, channel: &str, data: &EventData) -> Result<String> {
-    use chacha20poly1305::{
-        ChaCha20Poly1305, Nonce,
-        aead::{Aead, AeadCore, KeyInit, OsRng},
-    };
-
-    // Ensure master key is present
-    let _master_key =
-        pusher
-            .config()
-            .encryption_master_key()
-            .ok_or_else(|| PusherError::Encryption {
-                message: "Set encryptionMasterKey before triggering events on encrypted channels"
-                    .to_string(),
-            })?;
-
-    // Get channel shared secret
-    let shared_secret_bytes = pusher.channel_shared_secret(channel)?;
+/// Stub function when encryption is disabled
+#[cfg(not(feature = "encryption"))]
+pub(crate) fn encrypt(_pusher: &Pusher, _channel: &str, _data: &EventData) -> Result<String> {
+    Err(PusherError::Encryption {
+        message: "Encryption support is not enabled. Enable the 'encryption' feature to use encrypted channels.".to_string(),
+    })
+}
 
-    // Create cipher
-    let cipher = ChaCha20Poly1305::new_from_slice(&shared_secret_bytes).map_err(|_| {
-        PusherError::Encryption {
-            message: "Failed to create cipher from shared secret".to_string(),
-        }
-    })?;
+/// Encrypted event payload sent as the `data` of events on encrypted channels
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncryptedPayload {
+    /// Base64-encoded 24-byte nonce
+    pub nonce: String,
+    /// Base64-encoded secretbox ciphertext
+    pub ciphertext: String,
+}
 
-    // Generate random nonce
-    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+impl EncryptedPayload {
+    /// Parses the `{"nonce": ..., "ciphertext": ...}` JSON of an encrypted event
+    pub fn parse(data: &str) -> Result<Self> {
+        Ok(json::from_str(data)?)
+    }
+}
 
-    // Encrypt the data
-    let data_string = data.to_string();
-    let ciphertext = cipher
-        .encrypt(&nonce, data_string.as_bytes())
-        .map_err(|_| PusherError::Encryption {
-            message: "Encryption failed".to_string(),
-        })?;
+/// Decrypts data from an encrypted channel
+pub(crate) fn decrypt(pusher: &Pusher, channel: &str, data: &str) -> Result<String> {
+    decrypt_payload(pusher, channel, &EncryptedPayload::parse(data)?)
+}
 
-    // Return encrypted payload as JSON string
-    let encrypted_payload = json!({
-        "nonce": BASE64.encode(&nonce),
-        "ciphertext": BASE64.encode(&ciphertext),
-    });
+/// Decrypts a parsed payload from an encrypted channel
+#[cfg(feature = "encryption")]
+pub(crate) fn decrypt_payload(
+    pusher: &Pusher,
+    channel: &str,
+    payload: &EncryptedPayload,
+) -> Result<String> {
+    let invalid = |message: &str| PusherError::Encryption {
+        message: message.to_string(),
+    };
+    let nonce: [u8; 24] = BASE64
+        .decode(&payload.nonce)
+        .map_err(|_| invalid("Invalid nonce encoding"))?
+        .try_into()
+        .map_err(|_| invalid("Invalid nonce"))?;
+    let ciphertext = BASE64
+        .decode(&payload.ciphertext)
+        .map_err(|_| invalid("Invalid ciphertext encoding"))?;
+
+    let backend = pusher.config().encryption_backend();
+    let shared_secret_bytes = pusher.channel_shared_secret(channel)?;
+    let plaintext = match backend.open(&shared_secret_bytes, &nonce, &ciphertext) {
+        Ok(plaintext) => plaintext,
+        // Payloads encrypted before a master key rotation
+        Err(e) => match pusher.previous_channel_shared_secret(channel) {
+            Some(previous) => backend.open(&previous, &nonce, &ciphertext)?,
+            None => return Err(e),
+        },
+    };
 
-    Ok(sonic_rs::to_string(&encrypted_payload)?)
+    // Ownership of valid plaintext passes to the caller; rejected bytes are zeroized
+    String::from_utf8(plaintext).map_err(|e| {
+        drop(Zeroizing::new(e.into_bytes()));
+        invalid("Decrypted data is not valid UTF-8")
+    })
 }
 
 /// Stub function when encryption is disabled
 #[cfg(not(feature = "encryption"))]
-fn encrypt(_pusher: &Pusher, _channel: &str, _data: &EventData) -> Result<String> {
+pub(crate) fn decrypt_payload(
+    _pusher: &Pusher,
+    _channel: &str,
+    _payload: &EncryptedPayload,
+) -> Result<String> {
     Err(PusherError::Encryption {
         message: "Encryption support is not enabled. Enable the 'encryption' feature to use encrypted channels.".to_string(),
     })
 }
 
+/// Extracts the request options from optional trigger params
+fn request_options(params: Option<&TriggerParams>) -> RequestOptions {
+    params
+        .map(|p| p.request_options.clone())
+        .unwrap_or_default()
+}
+
+/// Options for one part of a request split over several POSTs
+///
+/// An explicit idempotency key gets `suffix` appended, so the server doesn't discard the
+/// later parts as duplicates of the first.
+pub(crate) fn part_options(options: &RequestOptions, suffix: impl fmt::Display) -> RequestOptions {
+    let mut options = options.clone();
+    if let Some(key) = options.idempotency_key.as_mut() {
+        *key = format!("{}-{}", key, suffix);
+    }
+    options
+}
+
 /// Triggers an event on channels
 pub async fn trigger<D: Into<EventData>>(
     pusher: &Pusher,
@@ -328,80 +807,118 @@ pub async fn trigger<D: Into<EventData>>(
     event_name: impl AsRef<str>,
     data: D,
     params: Option<&TriggerParams>,
-) -> Result<reqwest::Response> {
+) -> Result<Response> {
     let data = data.into();
     let event_name = event_name.as_ref();
 
-    // Validate event name
-    if event_name.len() > 200 {
-        return Err(PusherError::Validation {
-            message: format!("Event name too long: '{}' (max 200 characters)", event_name),
-        });
-    }
+    validate_event_name(pusher, event_name)?;
 
     // Convert channels to strings
     let channel_strings: Vec<String> = channels.iter().map(|c| c.full_name()).collect();
 
-    if channels.len() == 1 && channels[0].is_encrypted() {
-        #[cfg(feature = "encryption")]
-        {
-            let encrypted_data = encrypt(pusher, &channel_strings[0], &data)?;
-
-            let mut event = Event {
-                name: event_name.to_string(),
-                data: encrypted_data,
-                channels: channel_strings,
-                socket_id: None,
-                info: None,
-                tags: None,
-            };
-
-            if let Some(params) = params {
-                event.socket_id = params.socket_id.clone();
-                event.info = params.info.clone();
-                event.tags = params.tags.clone();
-            }
+    if channels.len() > 1 && channels.iter().any(|c| c.is_encrypted()) {
+        // Each encrypted channel needs its own ciphertext, so fan out as batch events
+        return trigger_as_batches(pusher, &channel_strings, event_name, &data, params).await;
+    }
 
-            let event_json = sonic_rs::to_value(&event)?;
-            pusher.post("/events", &event_json).await
-        }
+    let encrypted = channels.len() == 1 && channels[0].is_encrypted();
+    let payload = if encrypted {
+        pusher.ensure_master_key().await?;
+        encrypt(pusher, &channel_strings[0], &data)?
+    } else {
+        data.to_string()
+    };
+    validate_payload_size(pusher, &payload)?;
+
+    let mut event = Event {
+        name: event_name.to_string(),
+        data: payload,
+        channels: channel_strings,
+        socket_id: None,
+        info: None,
+        tags: None,
+    };
 
-        #[cfg(not(feature = "encryption"))]
-        {
-            Err(PusherError::Encryption {
-                message: "Encryption support is not enabled. Enable the 'encryption' feature to use encrypted channels.".to_string(),
+    if let Some(params) = params {
+        event.socket_id = params.socket_id.clone();
+        event.info = params.info.clone();
+        event.tags = params.tags.clone();
+    }
+
+    let event_json = json::to_value(&event)?;
+    let result = pusher
+        .post_with_options("/events", &event_json, &request_options(params))
+        .await;
+    pusher
+        .config()
+        .auditor()
+        .record(&event.channels, &event.name, &event.data, &result);
+    let response = result?;
+    crate::metrics::record_events_triggered(pusher.config(), 1, event.data.len());
+    crate::sink::mirror_event(pusher.config(), &event, encrypted.then_some(&data));
+    Ok(response)
+}
+
+/// Triggers one event per channel through `/batch_events`, in chunks of at most 10 events
+///
+/// Chunks are sent in order and the last chunk's response is returned. If a chunk fails
+/// after earlier ones were delivered, the error is a [`PusherError::Batch`] whose
+/// `failed_indices` are the positions in `channels` that were not delivered.
+async fn trigger_as_batches(
+    pusher: &Pusher,
+    channels: &[String],
+    event_name: &str,
+    data: &EventData,
+    params: Option<&TriggerParams>,
+) -> Result<Response> {
+    let data = data.to_string();
+    let options = request_options(params);
+    let mut last_response = None;
+
+    for (chunk_index, chunk) in channels.chunks(MAX_BATCH_EVENTS).enumerate() {
+        let batch = chunk
+            .iter()
+            .map(|channel| {
+                let mut event = BatchEvent::new(event_name, channel.as_str(), data.as_str());
+                if let Some(params) = params {
+                    event.socket_id = params.socket_id.clone();
+                    event.info = params.info.clone();
+                    event.tags = params.tags.clone();
+                }
+                event
             })
-        }
-    } else {
-        // Check for encrypted channels in multi-channel trigger
-        for channel in channels {
-            if channel.is_encrypted() {
-                return Err(PusherError::Validation {
-                    message:
-                        "You cannot trigger to multiple channels when using encrypted channels"
-                            .to_string(),
+            .collect();
+
+        let offset = chunk_index * MAX_BATCH_EVENTS;
+        match trigger_batch_with_options(pusher, batch, &part_options(&options, chunk_index)).await
+        {
+            Ok(response) => last_response = Some(response),
+            // Nothing was delivered yet, so the chunk's own error is accurate
+            Err(e) if offset == 0 => return Err(e),
+            Err(e) => {
+                let mut failed_indices = match &e {
+                    PusherError::Batch { failed_indices, .. } => {
+                        failed_indices.iter().map(|index| offset + index).collect()
+                    }
+                    _ => (offset..offset + chunk.len()).collect::<Vec<_>>(),
+                };
+                failed_indices.extend(offset + chunk.len()..channels.len());
+                return Err(PusherError::Batch {
+                    message: format!(
+                        "{} of {} channels delivered before a chunk failed: {}",
+                        channels.len() - failed_indices.len(),
+                        channels.len(),
+                        e
+                    ),
+                    failed_indices,
                 });
             }
         }
-
-        let mut event = Event {
-            name: event_name.to_string(),
-            data: data.to_string(),
-            channels: channel_strings,
-            socket_id: None,
-            info: None,
-            tags: None,
-        };
-
-        if let Some(params) = params {
-            event.socket_id = params.socket_id.clone();
-            event.info = params.info.clone();
-            event.tags = params.tags.clone();
-        }
-
-        let event_json = sonic_rs::to_value(&event)?;
-        pusher.post("/events", &event_json).await
     }
+
+    last_response.ok_or_else(|| PusherError::Validation {
+        message: "At least one channel is required".to_string(),
+    })
 }
 
 /// Triggers an event on channel names (backward compatibility)
@@ -411,17 +928,26 @@ pub async fn trigger_on_channels<D: Into<EventData>>(
     event_name: impl AsRef<str>,
     data: D,
     params: Option<&TriggerParams>,
-) -> Result<reqwest::Response> {
+) -> Result<Response> {
     let channels: Result<Vec<Channel>> = channels.iter().map(|c| Channel::from_string(c)).collect();
     let channels = channels?;
     trigger(pusher, &channels, event_name, data, params).await
 }
 
 /// Triggers a batch of events
-pub async fn trigger_batch(
+pub async fn trigger_batch(pusher: &Pusher, batch: Vec<BatchEvent>) -> Result<Response> {
+    trigger_batch_with_options(pusher, batch, &RequestOptions::default()).await
+}
+
+/// Triggers a batch of events with per-call request options
+///
+/// When an oversized batch is split, an explicit idempotency key gets the range of each
+/// sub-batch appended.
+pub async fn trigger_batch_with_options(
     pusher: &Pusher,
     mut batch: Vec<BatchEvent>,
-) -> Result<reqwest::Response> {
+    options: &RequestOptions,
+) -> Result<Response> {
     // Validate batch size
     if batch.is_empty() {
         return Err(PusherError::Validation {
@@ -429,39 +955,239 @@ pub async fn trigger_batch(
         });
     }
 
-    if batch.len() > 10 {
+    if batch.len() > MAX_BATCH_EVENTS {
         return Err(PusherError::Validation {
-            message: format!("Batch too large: {} events (max 10)", batch.len()),
+            message: format!(
+                "Batch too large: {} events (max {})",
+                batch.len(),
+                MAX_BATCH_EVENTS
+            ),
         });
     }
 
+    // Validate every event locally so one bad event doesn't fail the whole batch server-side
+    let mut encrypted = Vec::new();
+    for (index, event) in batch.iter().enumerate() {
+        if validate_batch_event(pusher, event).map_err(at_batch_event(index, event))? {
+            encrypted.push(index);
+        }
+    }
+
     // Encrypt data for encrypted channels
-    for event in &mut batch {
-        let channel = Channel::from_string(&event.channel)?;
-        if channel.is_encrypted() {
-            #[cfg(feature = "encryption")]
-            {
-                let data = EventData::String(event.data.clone());
-                event.data = encrypt(pusher, &event.channel, &data)?;
-            }
+    if !encrypted.is_empty() {
+        pusher.ensure_master_key().await?;
+    }
+    let plaintext = (!encrypted.is_empty() && crate::sink::wants_plaintext(pusher.config()))
+        .then(|| batch.iter().map(|e| e.data.clone()).collect::<Vec<_>>());
+    encrypt_batch_events(pusher, &mut batch, &encrypted).await?;
 
-            #[cfg(not(feature = "encryption"))]
-            {
-                return Err(PusherError::Encryption {
-                    message: "Encryption support is not enabled. Enable the 'encryption' feature to use encrypted channels.".to_string(),
+    for (index, event) in batch.iter().enumerate() {
+        validate_payload_size(pusher, &event.data).map_err(at_batch_event(index, event))?;
+    }
+
+    let result = post_batch(pusher, &batch, options).await;
+
+    let auditor = pusher.config().auditor();
+    if auditor.is_enabled() {
+        let events: Vec<(&str, &str, &str)> = batch
+            .iter()
+            .map(|e| (e.channel.as_str(), e.name.as_str(), e.data.as_str()))
+            .collect();
+        auditor.record_batch(&events, &result);
+    }
+    crate::sink::mirror_batch(
+        pusher.config(),
+        &batch,
+        &encrypted,
+        plaintext.as_deref(),
+        &result,
+    );
+
+    result
+}
+
+/// Encrypts the data of the given batch events in place
+///
+/// Larger sets are encrypted concurrently on the tokio runtime's blocking threads, if any.
+#[cfg(feature = "encryption")]
+async fn encrypt_batch_events(
+    pusher: &Pusher,
+    batch: &mut [BatchEvent],
+    indices: &[usize],
+) -> Result<()> {
+    #[cfg(feature = "tokio")]
+    if indices.len() >= PARALLEL_ENCRYPTION_THRESHOLD
+        && let Ok(runtime) = tokio::runtime::Handle::try_current()
+    {
+        let tasks: Vec<_> = indices
+            .iter()
+            .map(|&index| {
+                let pusher = pusher.clone();
+                let channel = batch[index].channel.clone();
+                let plaintext = Zeroizing::new(std::mem::take(&mut batch[index].data));
+                runtime.spawn_blocking(move || {
+                    encrypt_plaintext(&pusher, &channel, plaintext.as_bytes())
+                })
+            })
+            .collect();
+
+        for (&index, task) in indices.iter().zip(tasks) {
+            batch[index].data = task.await.map_err(|e| PusherError::Encryption {
+                message: format!("Encryption task failed: {}", e),
+            })??;
+        }
+        return Ok(());
+    }
+
+    // Encrypt inline when there are few channels or no tokio runtime to offload to
+    for &index in indices {
+        let plaintext = Zeroizing::new(std::mem::take(&mut batch[index].data));
+        batch[index].data = encrypt_plaintext(pusher, &batch[index].channel, plaintext.as_bytes())?;
+    }
+    Ok(())
+}
+
+/// Stub function when encryption is disabled
+#[cfg(not(feature = "encryption"))]
+async fn encrypt_batch_events(
+    _pusher: &Pusher,
+    _batch: &mut [BatchEvent],
+    indices: &[usize],
+) -> Result<()> {
+    if indices.is_empty() {
+        return Ok(());
+    }
+    Err(PusherError::Encryption {
+        message: "Encryption support is not enabled. Enable the 'encryption' feature to use encrypted channels.".to_string(),
+    })
+}
+
+/// Total size of the events' data fields
+fn data_bytes(batch: &[BatchEvent]) -> usize {
+    batch.iter().map(|event| event.data.len()).sum()
+}
+
+/// Finds the index of the event a batch error response blames, if any
+///
+/// Only a numeric `index` field in a JSON body is understood; free-text messages are not
+/// parsed, since their format is not part of the API.
+fn rejected_event_index(body: &str) -> Option<usize> {
+    json::from_str::<Value>(body)
+        .ok()?
+        .get("index")?
+        .as_u64()
+        .map(|index| index as usize)
+}
+
+/// Attaches the event a 4xx batch rejection blames to the error
+///
+/// Only used when the whole batch was sent in one request, so nothing was delivered.
+fn with_rejected_event(error: PusherError, sent: &[BatchEvent]) -> PusherError {
+    let PusherError::Request(mut e) = error else {
+        return error;
+    };
+    let index = match (e.status, e.body.as_deref()) {
+        (Some(400..=499), Some(body)) => rejected_event_index(body),
+        _ => None,
+    };
+    if let Some((index, event)) = index.and_then(|i| Some((i, sent.get(i)?))) {
+        e.rejected_event = Some(RejectedBatchEvent {
+            index,
+            channel: event.channel.clone(),
+            name: event.name.clone(),
+        });
+    }
+    PusherError::Request(e)
+}
+
+/// Posts a prepared batch, bisecting it on 413 if configured
+async fn post_batch(
+    pusher: &Pusher,
+    batch: &[BatchEvent],
+    options: &RequestOptions,
+) -> Result<Response> {
+    if !pusher.config().split_oversized_batches() {
+        let batch_payload = json!({ "batch": batch });
+        let response = pusher
+            .post_with_options("/batch_events", &batch_payload, options)
+            .await
+            .map_err(|e| with_rejected_event(e, batch))?;
+        crate::metrics::record_events_triggered(pusher.config(), batch.len(), data_bytes(batch));
+        return Ok(response);
+    }
+
+    // Bisect batches rejected with 413 until the oversized events are isolated
+    let mut pending = vec![(0, batch.len())];
+    let mut delivered = vec![false; batch.len()];
+    let mut oversized = Vec::new();
+    let mut last_response = None;
+
+    while let Some((start, end)) = pending.pop() {
+        let batch_payload = json!({ "batch": &batch[start..end] });
+        let options = if end - start == batch.len() {
+            options.clone()
+        } else {
+            part_options(options, format_args!("{}-{}", start, end))
+        };
+        match pusher
+            .post_with_options("/batch_events", &batch_payload, &options)
+            .await
+        {
+            Ok(response) => {
+                crate::metrics::record_events_triggered(
+                    pusher.config(),
+                    end - start,
+                    data_bytes(&batch[start..end]),
+                );
+                delivered[start..end].fill(true);
+                last_response = Some(response);
+            }
+            Err(PusherError::Request(ref e)) if e.status == Some(413) => {
+                if end - start == 1 {
+                    oversized.push(start);
+                } else {
+                    let mid = start + (end - start) / 2;
+                    pending.push((mid, end));
+                    pending.push((start, mid));
+                }
+            }
+            // Only the unsplit batch is known to have been rejected without any delivery
+            Err(e) if start == 0 && end == batch.len() => {
+                return Err(with_rejected_event(e, batch));
+            }
+            Err(e) => {
+                let failed_indices: Vec<usize> = (0..batch.len())
+                    .filter(|&index| !delivered[index])
+                    .collect();
+                return Err(PusherError::Batch {
+                    message: format!(
+                        "{} of {} events delivered before a sub-batch failed: {}",
+                        batch.len() - failed_indices.len(),
+                        batch.len(),
+                        e
+                    ),
+                    failed_indices,
                 });
             }
         }
     }
 
-    let batch_payload = json!({ "batch": batch });
-    pusher.post("/batch_events", &batch_payload).await
+    match last_response {
+        Some(response) if oversized.is_empty() => Ok(response),
+        _ => Err(PusherError::Batch {
+            message: format!(
+                "{} of {} events rejected as too large; the others were delivered",
+                oversized.len(),
+                batch.len()
+            ),
+            failed_indices: oversized,
+        }),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use sonic_rs::json;
 
     #[test]
     fn test_event_data_conversions() {
@@ -482,6 +1208,277 @@ mod tests {
         assert!(matches!(data, EventData::Json(_)));
     }
 
+    #[test]
+    fn test_event_data_binary() {
+        let frame = vec![0x08, 0x96, 0x01, 0xff];
+        let data = EventData::from_bytes(frame.clone());
+        assert_eq!(data.to_string(), "CJYB/w==");
+        assert_eq!(data.as_json().unwrap(), json!("CJYB/w=="));
+        assert_eq!(data.to_bytes().unwrap(), frame);
+
+        let received = EventData::from_string(data.to_string());
+        assert_eq!(received.to_bytes().unwrap(), frame);
+        assert_eq!(
+            EventData::from_json(json!("CJYB/w==")).to_bytes().unwrap(),
+            frame
+        );
+        assert!(EventData::from_string("not base64!").to_bytes().is_err());
+        assert!(EventData::from_json(json!({"a": 1})).to_bytes().is_err());
+
+        let data: EventData = frame.as_slice().into();
+        assert!(matches!(data, EventData::Binary(_)));
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn test_derive_pusher_event() {
+        #[derive(Serialize, crate::PusherEvent)]
+        #[pusher(name = "order-updated")]
+        struct OrderUpdated {
+            id: u64,
+        }
+
+        #[derive(Serialize, crate::PusherEvent)]
+        struct OrderShipped {}
+
+        assert_eq!(OrderUpdated::NAME, "order-updated");
+        assert_eq!(OrderShipped::NAME, "order-shipped");
+
+        let batch = BatchEvent::from_event("orders", &OrderUpdated { id: 42 }).unwrap();
+        assert_eq!(batch.name, "order-updated");
+        assert_eq!(batch.data, r#"{"id":42}"#);
+    }
+
+    #[test]
+    fn test_batch_event_from_serialize() {
+        #[derive(Serialize)]
+        struct Order<'a> {
+            id: u64,
+            note: &'a str,
+        }
+
+        let event =
+            BatchEvent::from_serialize("created", "orders", &Order { id: 1, note: "hi" }).unwrap();
+        assert_eq!(event.data, r#"{"id":1,"note":"hi"}"#);
+        assert_eq!(event.channel, "orders");
+
+        let large = "a".repeat(DEFAULT_MAX_EVENT_PAYLOAD_BYTES);
+        let err = BatchEvent::from_serialize("created", "orders", &large).unwrap_err();
+        assert!(matches!(err, PusherError::Validation { .. }));
+        assert!(BatchEvent::from_serialize_with_limit("created", "orders", &large, 20_000).is_ok());
+    }
+
+    #[test]
+    fn test_trigger_params_round_trip() {
+        let params = TriggerParams::builder()
+            .socket_id("123.456")
+            .info("user_count")
+            .idempotency_key("key-1")
+            .build();
+        let stored = json::to_string(&params).unwrap();
+        assert_eq!(
+            stored,
+            r#"{"socket_id":"123.456","info":"user_count","idempotency_key":"key-1"}"#
+        );
+
+        let loaded: TriggerParams = json::from_str(&stored).unwrap();
+        assert_eq!(loaded, params);
+        assert_eq!(
+            loaded.request_options.idempotency_key.as_deref(),
+            Some("key-1")
+        );
+        assert_ne!(
+            loaded,
+            TriggerParams::builder()
+                .socket_id("123.456")
+                .info("user_count")
+                .idempotency_key("key-2")
+                .build()
+        );
+        assert_eq!(
+            json::from_str::<TriggerParams>("{}").unwrap(),
+            TriggerParams::default()
+        );
+    }
+
+    #[test]
+    fn test_event_builder() {
+        let orders = Channel::from_string("orders").unwrap();
+        let event = Event::builder()
+            .name("created")
+            .channel(&orders)
+            .channels([Channel::from_string("private-admin").unwrap()])
+            .data(json!({"id": 1}))
+            .socket_id("123.456")
+            .build()
+            .unwrap();
+        assert_eq!(event.channels, vec!["orders", "private-admin"]);
+        assert_eq!(event.data, r#"{"id":1}"#);
+
+        let stored = json::to_string(&event).unwrap();
+        let replayed: Event = json::from_str(&stored).unwrap();
+        assert_eq!(replayed, event);
+
+        let invalid = [
+            Event::builder().channel(&orders).data("{}"),
+            Event::builder().name("e".repeat(201)).channel(&orders),
+            Event::builder().name("created"),
+            Event::builder()
+                .name("created")
+                .channel(Channel::from_string("private-encrypted-orders").unwrap()),
+            Event::builder()
+                .name("created")
+                .channel(&orders)
+                .socket_id("invalid"),
+            Event::builder()
+                .name("created")
+                .channel(&orders)
+                .data("x".repeat(11))
+                .max_payload_bytes(10),
+            Event::builder()
+                .name("created")
+                .channels(std::iter::repeat_n(&orders, MAX_TRIGGER_CHANNELS + 1)),
+        ];
+        for builder in invalid {
+            assert!(matches!(
+                builder.build(),
+                Err(PusherError::Validation { .. })
+            ));
+        }
+    }
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn test_encrypt_decrypt_roundtrip() {
+        let config = crate::Config::builder()
+            .app_id("123")
+            .key("key")
+            .secret("secret")
+            .encryption_master_key_base64("MDEyMzQ1Njc4OWFiY2RlZjAxMjM0NTY3ODlhYmNkZWY=")
+            .unwrap()
+            .build()
+            .unwrap();
+        let pusher = Pusher::new(config).unwrap();
+        let channel = "private-encrypted-test";
+
+        let encrypted = encrypt(&pusher, channel, &EventData::from_string("secret data")).unwrap();
+        assert_eq!(
+            decrypt(&pusher, channel, &encrypted).unwrap(),
+            "secret data"
+        );
+        assert!(decrypt(&pusher, "private-encrypted-other", &encrypted).is_err());
+
+        let payload = EncryptedPayload::parse(&encrypted).unwrap();
+        assert_eq!(
+            pusher.decrypt_event(channel, &payload).unwrap(),
+            EventData::from_string("secret data")
+        );
+
+        let encrypted = pusher.encrypt_for_channel(channel, "secret data").unwrap();
+        assert_eq!(
+            decrypt(&pusher, channel, &encrypted).unwrap(),
+            "secret data"
+        );
+        assert!(pusher.encrypt_for_channel("private-test", "data").is_err());
+    }
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn test_custom_encryption_backend() {
+        use crate::encryption::{EncryptionBackend, SecretboxBackend};
+
+        struct FixedNonce;
+        impl EncryptionBackend for FixedNonce {
+            fn generate_nonce(&self) -> Result<[u8; 24]> {
+                Ok([1; 24])
+            }
+            fn seal(&self, key: &[u8; 32], nonce: &[u8; 24], plaintext: &[u8]) -> Result<Vec<u8>> {
+                SecretboxBackend.seal(key, nonce, plaintext)
+            }
+            fn open(&self, key: &[u8; 32], nonce: &[u8; 24], ciphertext: &[u8]) -> Result<Vec<u8>> {
+                SecretboxBackend.open(key, nonce, ciphertext)
+            }
+        }
+
+        let config = crate::Config::builder()
+            .app_id("123")
+            .key("key")
+            .secret("secret")
+            .encryption_master_key(vec![9u8; 32])
+            .unwrap()
+            .encryption_backend(FixedNonce)
+            .build()
+            .unwrap();
+        let pusher = Pusher::new(config).unwrap();
+        let channel = "private-encrypted-test";
+
+        let encrypted = encrypt(&pusher, channel, &EventData::from_string("data")).unwrap();
+        let payload = EncryptedPayload::parse(&encrypted).unwrap();
+        assert_eq!(payload.nonce, BASE64.encode([1u8; 24]));
+        assert_eq!(decrypt(&pusher, channel, &encrypted).unwrap(), "data");
+    }
+
+    #[cfg(feature = "encryption")]
+    #[tokio::test]
+    async fn test_multi_channel_encrypted_trigger_uses_batches() {
+        use crate::interceptor::{BoxFuture, Interceptor, RequestParts};
+        use std::sync::{Arc, Mutex};
+
+        struct Capture(Arc<Mutex<Vec<RequestParts>>>);
+        impl Interceptor for Capture {
+            fn before_send<'a>(
+                &'a self,
+                request: &'a mut RequestParts,
+            ) -> BoxFuture<'a, Result<()>> {
+                self.0.lock().unwrap().push(request.clone());
+                Box::pin(async { Ok(()) })
+            }
+        }
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let config = crate::Config::builder()
+            .app_id("123")
+            .key("key")
+            .secret("secret")
+            .host("127.0.0.1")
+            .port(1)
+            .use_tls(false)
+            .enable_retry(false)
+            .encryption_master_key(vec![9u8; 32])
+            .unwrap()
+            .interceptor(Capture(seen.clone()))
+            .build()
+            .unwrap();
+        let pusher = Pusher::new(config).unwrap();
+        let channels: Vec<Channel> = (0..12)
+            .map(|i| Channel::from_string(format!("private-encrypted-{}", i)).unwrap())
+            .collect();
+
+        // Nothing listens on port 1, so only the first chunk is attempted
+        assert!(
+            trigger(&pusher, &channels, "event", "data", None)
+                .await
+                .is_err()
+        );
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 1);
+        assert!(seen[0].url.contains("/batch_events"));
+        #[derive(Deserialize)]
+        struct Body {
+            batch: Vec<BatchEvent>,
+        }
+        let batch = json::from_str::<Body>(seen[0].body.as_deref().unwrap())
+            .unwrap()
+            .batch;
+        assert_eq!(batch.len(), MAX_BATCH_EVENTS);
+        assert_ne!(batch[0].data, batch[1].data);
+        assert_eq!(
+            decrypt(&pusher, &batch[1].channel, &batch[1].data).unwrap(),
+            "data"
+        );
+    }
+
     #[test]
     fn test_batch_event_builder() {
         let event = BatchEvent::new("test-event", "test-channel", "test-data")
@@ -527,4 +1524,187 @@ mod tests {
 
         assert_eq!(params.tags, Some(tags));
     }
+
+    #[test]
+    fn test_trigger_params_builder_with_idempotency_key() {
+        let params = TriggerParams::builder().idempotency_key("key-1").build();
+
+        assert_eq!(
+            params.request_options.idempotency_key,
+            Some("key-1".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reject_client_event_names() {
+        let pusher = Pusher::new(crate::Config::new("123", "key", "secret")).unwrap();
+        assert!(validate_event_name(&pusher, "client-typing").is_ok());
+
+        let config = crate::Config::builder()
+            .app_id("123")
+            .key("key")
+            .secret("secret")
+            .reject_client_event_names(true)
+            .build()
+            .unwrap();
+        let pusher = Pusher::new(config).unwrap();
+        assert!(validate_event_name(&pusher, "typing").is_ok());
+
+        let channel = Channel::from_string("orders").unwrap();
+        let result = pusher.trigger([channel], "client-typing", "{}", None).await;
+        assert!(matches!(result, Err(PusherError::Validation { .. })));
+
+        let batch = vec![BatchEvent::new("client-typing", "orders", "{}")];
+        let result = pusher.trigger_batch(batch).await;
+        assert!(matches!(result, Err(PusherError::InvalidBatchEvent { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_trigger_batch_reports_invalid_event_index() {
+        async fn batch_error(
+            pusher: &Pusher,
+            batch: Vec<BatchEvent>,
+        ) -> (String, RejectedBatchEvent) {
+            match pusher.trigger_batch(batch).await {
+                Err(PusherError::InvalidBatchEvent { message, event }) => (message, event),
+                other => panic!("expected invalid batch event, got {:?}", other),
+            }
+        }
+        let pusher = Pusher::new(crate::Config::new("123", "key", "secret")).unwrap();
+
+        let batch = vec![
+            BatchEvent::new("created", "orders", "{}"),
+            BatchEvent::new("created", "invoices", "{}").with_socket_id("not-a-socket"),
+        ];
+        let (message, event) = batch_error(&pusher, batch).await;
+        assert!(message.starts_with("Invalid socket id"), "{}", message);
+        assert_eq!(
+            event,
+            RejectedBatchEvent {
+                index: 1,
+                channel: "invoices".to_string(),
+                name: "created".to_string(),
+            }
+        );
+
+        let batch = vec![
+            BatchEvent::new("", "orders", "{}"),
+            BatchEvent::new("created", "orders", "{}"),
+        ];
+        assert_eq!(batch_error(&pusher, batch).await.1.index, 0);
+
+        let batch = vec![
+            BatchEvent::new("created", "orders", "{}"),
+            BatchEvent::new("created", "orders", "{}"),
+            BatchEvent::new("x".repeat(201), "orders", "{}"),
+        ];
+        let error = pusher.trigger_batch(batch).await.unwrap_err();
+        assert!(error.is_validation_error());
+        assert_eq!(error.rejected_batch_event().unwrap().index, 2);
+        assert!(
+            error
+                .to_string()
+                .starts_with("Validation error: Batch event 2: Event name too long"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn test_rejected_event_index() {
+        assert_eq!(
+            rejected_event_index(r#"{"error": "bad", "index": 2}"#),
+            Some(2)
+        );
+        assert_eq!(rejected_event_index(r#"{"index": "2"}"#), None);
+        assert_eq!(
+            rejected_event_index("Invalid event at index 3: channel name too long"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_with_rejected_event() {
+        let batch = vec![
+            BatchEvent::new("a", "one", "{}"),
+            BatchEvent::new("b", "two", "{}"),
+        ];
+        let error = |status, body: &str| {
+            PusherError::from(crate::RequestError::new(
+                "HTTP error",
+                "/apps/1/batch_events",
+                Some(status),
+                Some(body.to_string()),
+            ))
+        };
+
+        let rejected = with_rejected_event(error(400, r#"{"index":1}"#), &batch);
+        assert_eq!(
+            rejected.rejected_batch_event(),
+            Some(&RejectedBatchEvent {
+                index: 1,
+                channel: "two".to_string(),
+                name: "b".to_string(),
+            })
+        );
+        let out_of_range = with_rejected_event(error(400, r#"{"index":7}"#), &batch);
+        assert_eq!(out_of_range.rejected_batch_event(), None);
+        let server_error = with_rejected_event(error(500, r#"{"index":0}"#), &batch);
+        assert_eq!(server_error.rejected_batch_event(), None);
+    }
+
+    #[test]
+    fn test_validate_payload_size() {
+        let config = crate::Config::builder()
+            .app_id("123")
+            .key("key")
+            .secret("secret")
+            .max_event_payload_bytes(16)
+            .build()
+            .unwrap();
+        let pusher = Pusher::new(config).unwrap();
+
+        assert!(validate_payload_size(&pusher, "small").is_ok());
+        assert!(validate_payload_size(&pusher, &"a".repeat(16)).is_ok());
+
+        let err = validate_payload_size(&pusher, &"a".repeat(17)).unwrap_err();
+        assert!(err.to_string().contains("17 bytes"));
+    }
+
+    #[test]
+    fn test_batch_builder_flushes_on_limits() {
+        let mut builder = BatchBuilder::new();
+        for i in 0..10 {
+            let flushed = builder
+                .push(BatchEvent::new("e", format!("c{}", i), "x"))
+                .unwrap();
+            assert!(flushed.is_none());
+        }
+        assert!(builder.is_full());
+
+        let flushed = builder.push(BatchEvent::new("e", "c10", "x")).unwrap();
+        assert_eq!(flushed.map(|batch| batch.len()), Some(10));
+        assert_eq!(builder.len(), 1);
+        assert_eq!(builder.finish().unwrap()[0].channel, "c10");
+
+        let mut builder = BatchBuilder::new().max_batch_bytes(10);
+        assert!(
+            builder
+                .push(BatchEvent::new("e", "a", "123456"))
+                .unwrap()
+                .is_none()
+        );
+        let flushed = builder.push(BatchEvent::new("e", "b", "123456")).unwrap();
+        assert_eq!(flushed.unwrap()[0].channel, "a");
+        assert_eq!(builder.data_bytes(), 6);
+
+        assert!(
+            builder
+                .push(BatchEvent::new("e", "c", "x".repeat(11)))
+                .is_err()
+        );
+        assert!(builder.push(BatchEvent::new("", "c", "x")).is_err());
+        assert_eq!(builder.len(), 1);
+        assert!(BatchBuilder::new().finish().is_none());
+    }
 }
@@ -0,0 +1,125 @@
+//! Request/response hooks chained around every HTTP attempt
+
+use crate::Result;
+use http::HeaderMap;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Boxed future returned by interceptor hooks
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Mutable view of an outgoing request
+///
+/// The URL already carries the auth signature, so changing the path, query, or body
+/// will invalidate it. Headers can be added or changed freely.
+#[derive(Debug, Clone)]
+pub struct RequestParts {
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<String>,
+    /// 1-based attempt number
+    pub attempt: u32,
+}
+
+/// Read-only view of a received response
+#[derive(Debug, Clone)]
+pub struct ResponseParts {
+    pub method: String,
+    pub url: String,
+    /// Response status, if a response was received
+    pub status: Option<u16>,
+    pub headers: HeaderMap,
+    /// Network error message, if no response was received
+    pub error: Option<String>,
+    /// Time spent on this attempt
+    pub elapsed: Duration,
+    /// 1-based attempt number
+    pub attempt: u32,
+}
+
+/// Hooks invoked before each HTTP attempt is sent and after it completes
+///
+/// Interceptors run in the order they were registered. Returning an error from
+/// `before_send` aborts the request without retrying.
+pub trait Interceptor: Send + Sync {
+    /// Called before the request is sent
+    fn before_send<'a>(&'a self, _request: &'a mut RequestParts) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    /// Called after a response (or network error) is received
+    fn after_receive<'a>(&'a self, _response: &'a ResponseParts) -> BoxFuture<'a, ()> {
+        Box::pin(async {})
+    }
+}
+
+/// Shared handle to an interceptor stored in the configuration
+#[derive(Clone)]
+pub(crate) struct InterceptorHandle(pub(crate) Arc<dyn Interceptor>);
+
+impl fmt::Debug for InterceptorHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Interceptor")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Config, Pusher, json::json};
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Recorder {
+        seen: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl Interceptor for Recorder {
+        fn before_send<'a>(&'a self, request: &'a mut RequestParts) -> BoxFuture<'a, Result<()>> {
+            Box::pin(async move {
+                request
+                    .headers
+                    .push(("X-Custom".to_string(), "1".to_string()));
+                self.seen
+                    .lock()
+                    .unwrap()
+                    .push(format!("before {}", request.attempt));
+                Ok(())
+            })
+        }
+
+        fn after_receive<'a>(&'a self, response: &'a ResponseParts) -> BoxFuture<'a, ()> {
+            Box::pin(async move {
+                self.seen.lock().unwrap().push(format!(
+                    "after {} {}",
+                    response.attempt,
+                    response.error.is_some()
+                ));
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_interceptor_hooks_run_per_attempt() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let config = Config::builder()
+            .app_id("123")
+            .key("key")
+            .secret("secret")
+            .host("127.0.0.1")
+            .port(1)
+            .use_tls(false)
+            .enable_retry(false)
+            .interceptor(Recorder { seen: seen.clone() })
+            .build()
+            .unwrap();
+        let pusher = Pusher::new(config).unwrap();
+
+        assert!(pusher.post("/events", &json!({})).await.is_err());
+        assert_eq!(*seen.lock().unwrap(), vec!["before 1", "after 1 true"]);
+    }
+}
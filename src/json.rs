@@ -0,0 +1,86 @@
+//! JSON types and backend used throughout the crate
+//!
+//! Event serialization, request bodies and webhook parsing all go through the
+//! SIMD-accelerated `sonic-rs` library, whose [`Value`] and [`json!`] are the public JSON
+//! types. Failures are reported as this module's [`Error`], which does not expose the
+//! backend's error type.
+
+use std::fmt;
+
+pub use sonic_rs::{JsonValueTrait, Value, json};
+
+/// JSON serialization or parsing error
+#[derive(Debug)]
+pub struct Error(sonic_rs::Error);
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+impl From<sonic_rs::Error> for Error {
+    fn from(error: sonic_rs::Error) -> Self {
+        Error(error)
+    }
+}
+
+/// Serializes `value` to a JSON string
+pub fn to_string<T: ?Sized + serde::Serialize>(value: &T) -> Result<String, Error> {
+    Ok(sonic_rs::to_string(value)?)
+}
+
+/// Converts `value` to a JSON [`Value`]
+pub fn to_value<T: ?Sized + serde::Serialize>(value: &T) -> Result<Value, Error> {
+    Ok(sonic_rs::to_value(value)?)
+}
+
+/// Parses a JSON string
+pub fn from_str<'a, T: serde::Deserialize<'a>>(json: &'a str) -> Result<T, Error> {
+    Ok(sonic_rs::from_str(json)?)
+}
+
+/// Parses JSON bytes
+pub fn from_slice<'a, T: serde::Deserialize<'a>>(json: &'a [u8]) -> Result<T, Error> {
+    Ok(sonic_rs::from_slice(json)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_round_trip() {
+        let value = json!({"name": "event", "data": "{\"a\":1}", "channels": ["a", "b"]});
+        let serialized = to_string(&value).unwrap();
+        let parsed: Value = from_str(&serialized).unwrap();
+        assert_eq!(parsed, value);
+    }
+
+    #[test]
+    fn test_serialization() {
+        // Bodies are serialized once and body_md5 is computed over that exact string
+        assert_eq!(
+            to_string(&json!({"name": "Tést \"user\""})).unwrap(),
+            r#"{"name":"Tést \"user\""}"#
+        );
+
+        let mut map = HashMap::new();
+        map.insert("name".to_string(), "channel_occupied".to_string());
+        assert_eq!(to_string(&map).unwrap(), r#"{"name":"channel_occupied"}"#);
+    }
+
+    #[test]
+    fn test_error_type() {
+        let error = from_str::<Value>("{").unwrap_err();
+        assert!(!error.to_string().is_empty());
+        assert!(std::error::Error::source(&error).is_some());
+    }
+}
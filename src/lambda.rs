@@ -0,0 +1,64 @@
+//! AWS Lambda (`lambda_http`) webhook adapter
+//!
+//! API Gateway bodies flagged as base64-encoded are decoded by `lambda_http` into
+//! `Body::Binary`, so the signature is always checked over the original bytes.
+//! Header lookup is case-insensitive.
+
+use crate::{Token, Webhook};
+use lambda_http::http::StatusCode;
+use lambda_http::{Body, Request, Response};
+
+/// Builds a webhook from a `lambda_http` request
+pub fn webhook_from_request(token: &Token, request: &Request) -> Webhook {
+    Webhook::from_http_parts(token, request.headers(), request.body().as_ref())
+}
+
+/// Builds a webhook from a `lambda_http` request, accepting signatures from any of the given tokens
+pub fn webhook_from_request_with_tokens(tokens: &[Token], request: &Request) -> Webhook {
+    Webhook::from_http_parts_with_tokens(tokens, request.headers(), request.body().as_ref())
+}
+
+/// Returns `200 OK` for a valid webhook and `401 Unauthorized` otherwise
+pub fn webhook_response(webhook: &Webhook) -> Response<Body> {
+    let status = if webhook.is_valid(None) {
+        StatusCode::OK
+    } else {
+        StatusCode::UNAUTHORIZED
+    };
+
+    let mut response = Response::new(Body::Empty);
+    *response.status_mut() = status;
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_webhook_from_lambda_request() {
+        let token = Token::new("test_key", "test_secret");
+        let body = r#"{"time_ms":1234567890,"events":[]}"#;
+
+        let request = lambda_http::http::Request::builder()
+            .header("Content-Type", "application/json")
+            .header("X-Pusher-Key", "test_key")
+            .header("X-Pusher-Signature", token.sign(body))
+            .body(Body::Binary(body.as_bytes().to_vec()))
+            .unwrap();
+        let webhook = webhook_from_request(&token, &request);
+        assert_eq!(webhook_response(&webhook).status(), StatusCode::OK);
+
+        let request = lambda_http::http::Request::builder()
+            .header("content-type", "application/json")
+            .header("x-pusher-key", "test_key")
+            .header("x-pusher-signature", "bad")
+            .body(Body::Text(body.to_string()))
+            .unwrap();
+        let webhook = webhook_from_request(&token, &request);
+        assert_eq!(
+            webhook_response(&webhook).status(),
+            StatusCode::UNAUTHORIZED
+        );
+    }
+}
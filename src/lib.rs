@@ -4,9 +4,35 @@
 //!
 //! # Features
 //!
+//! - `reqwest` (default): Send requests with a pooled `reqwest` client; without it, set an
+//!   [`HttpTransport`] with `ConfigBuilder::transport`
 //! - `rustls-tls` (default): Use rustls for TLS (recommended for cross-compilation)
 //! - `native-tls`: Use native TLS (OpenSSL on Linux, Secure Transport on macOS, SChannel on Windows)
-//! - `encryption` (default): Enable support for end-to-end encrypted channels
+//! - `encryption` (default): Enable support for end-to-end encrypted channels (pure-Rust secretbox)
+//! - `sodiumoxide`: Use libsodium for encrypted channels instead of the pure-Rust implementation
+//! - `beams`: Publish Pusher Beams push notifications and generate Beams auth tokens
+//! - `blocking`: `blocking::Pusher`, a synchronous client for non-async applications
+//! - `derive`: `#[derive(PusherEvent)]` for typed events triggered with `Pusher::trigger_event`
+//! - `cli`: Build the `pusher` command-line tool (trigger, channel queries, auth, webhook checks)
+//! - `metrics`: Emit request, retry, and event counters through the `metrics` facade
+//! - `prometheus`: Register request, retry, failure, and event collectors into a
+//!   `prometheus::Registry`
+//! - `opentelemetry`: Provide an OpenTelemetry-backed `TracePropagator`
+//! - `debug-recorder`: Record sanitized request/response pairs to JSONL and replay them in tests
+//! - `http`: Construct webhooks directly from `http::Request` and `http::HeaderMap`
+//! - `actix`: `FromRequest` extractors for webhooks and channel auth requests in actix-web
+//! - `lambda`: Build webhooks from `lambda_http` requests and return 2xx/401 responses
+//! - `axum`: Drop-in axum router serving `/pusher/auth` and `/pusher/user-auth`
+//! - `md5` (default): Pure-Rust MD5 for `body_md5`; disable it and set `ConfigBuilder::body_digest`
+//!   where the `md5` crate cannot be linked (e.g. FIPS builds)
+//! - `secrecy`: Accept `secrecy::SecretString`/`SecretSlice` for the app secret and master key
+//! - `tokio` (default, implied by `reqwest`): tokio timers, `CancellationToken` support and
+//!   blocking-pool encryption of large fan-outs; without it, set a `Sleeper` with
+//!   `ConfigBuilder::sleeper` and an [`HttpTransport`] to run on any executor
+//! - `async-std` / `smol`: Timers for retry backoff and deadlines on async-std or smol
+//! - `tower-service`: `tower::Service<TriggerRequest>` for `Pusher`, to compose tower middleware
+//! - `test-util`: `WebhookTestBuilder` and `MockPusher` for testing webhook handlers and triggers
+//! - `wiremock`: `SignedRequest` matcher that verifies signatures of requests hitting a mock server
 //!
 //! # Cross-Compilation
 //!
@@ -20,32 +46,105 @@
 
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
+#[cfg(feature = "actix")]
+#[cfg_attr(docsrs, doc(cfg(feature = "actix")))]
+pub mod actix;
+pub mod audit;
 pub mod auth;
+#[cfg(feature = "axum")]
+#[cfg_attr(docsrs, doc(cfg(feature = "axum")))]
+pub mod axum;
+#[cfg(feature = "beams")]
+#[cfg_attr(docsrs, doc(cfg(feature = "beams")))]
+pub mod beams;
+#[cfg(feature = "blocking")]
+#[cfg_attr(docsrs, doc(cfg(feature = "blocking")))]
+pub mod blocking;
 pub mod channel;
+pub mod chunked;
+pub mod client;
+pub mod clock;
 pub mod config;
+pub mod dedup;
+pub mod delegation;
+pub mod digest;
+pub mod encryption;
+pub mod envelope;
 pub mod errors;
 pub mod events;
+pub mod interceptor;
+pub mod json;
+#[cfg(feature = "lambda")]
+#[cfg_attr(docsrs, doc(cfg(feature = "lambda")))]
+pub mod lambda;
+mod metrics;
+#[cfg(feature = "prometheus")]
+#[cfg_attr(docsrs, doc(cfg(feature = "prometheus")))]
+pub mod prometheus;
 pub mod pusher;
+pub mod ratelimit;
+#[cfg(feature = "debug-recorder")]
+#[cfg_attr(docsrs, doc(cfg(feature = "debug-recorder")))]
+pub mod recorder;
+pub mod server;
+#[cfg(feature = "tower-service")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tower-service")))]
+pub mod service;
+pub mod signer;
+pub mod signing;
+pub mod sink;
+pub mod sleep;
+#[cfg(feature = "test-util")]
+#[cfg_attr(docsrs, doc(cfg(feature = "test-util")))]
+pub mod test_util;
 pub mod token;
+pub mod trace;
+pub mod transport;
 pub mod util;
 pub mod webhook;
 
 #[macro_use]
 extern crate zeroize;
 
-pub use channel::{Channel, ChannelName, ChannelType};
-pub use config::{Config, ConfigBuilder};
-pub use errors::{PusherError, RequestError, WebhookError};
-pub use pusher::Pusher;
+// Lets `#[derive(PusherEvent)]` expand to `::pushers::...` inside this crate's tests
+#[cfg(all(test, feature = "derive"))]
+extern crate self as pushers;
+
+pub use channel::{
+    Channel, ChannelBuilder, ChannelName, ChannelPattern, ChannelSet, ChannelType,
+    MAX_TRIGGER_CHANNELS,
+};
+pub use client::PusherClient;
+pub use config::{Config, ConfigBuilder, ConfigPatch};
+pub use dedup::WebhookDeduplicator;
+pub use delegation::{DelegationGrant, DelegationIssuer, DelegationVerifier};
+pub use envelope::Envelope;
+pub use errors::{
+    AttemptInfo, CredentialError, DelegationError, HttpError, PusherError, RejectedBatchEvent,
+    RequestError, RequestVerificationError, WebhookError, WebhookValidationError,
+};
+pub use pusher::{IdempotencyKey, Pusher, RequestOptions, RetryMode};
+pub use ratelimit::RateLimit;
+pub use signer::RequestSigner;
 pub use token::Token;
-pub use webhook::{Webhook, WebhookEvent};
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+pub use tokio_util::sync::CancellationToken;
+pub use transport::{HttpTransport, Response};
+pub use webhook::{
+    ClientEvent, Tolerance, ValidatedWebhook, Webhook, WebhookEvent, WebhookHandler,
+    WebhookVerifier,
+};
 
 /// Result type alias for Pusher operations
 pub type Result<T> = std::result::Result<T, PusherError>;
 
 // Re-export commonly used types
-pub use auth::{SocketAuth, UserAuth};
-pub use events::{BatchEvent, Event, TriggerParams};
+pub use auth::{AuthRequest, PresenceMember, SocketAuth, UserAuth, UserData};
+pub use events::{BatchBuilder, BatchEvent, EncryptedPayload, Event, PusherEvent, TriggerParams};
+#[cfg(feature = "derive")]
+#[cfg_attr(docsrs, doc(cfg(feature = "derive")))]
+pub use pushers_derive::PusherEvent;
 
 /// Check if encryption support is available at compile time
 pub const ENCRYPTION_AVAILABLE: bool = cfg!(feature = "encryption");
@@ -70,13 +169,18 @@ impl BuildInfo {
         }
     }
 
+    /// Returns the JSON backend being used
+    pub fn json_backend() -> &'static str {
+        "sonic-rs"
+    }
+
     /// Returns the encryption backend being used
     #[cfg(feature = "encryption")]
     pub fn encryption_backend() -> &'static str {
         if cfg!(feature = "sodiumoxide") {
             "sodiumoxide"
         } else {
-            "chacha20poly1305"
+            "crypto_secretbox"
         }
     }
 }
@@ -89,6 +193,7 @@ mod tests {
     fn test_build_info() {
         println!("Encryption available: {}", BuildInfo::has_encryption());
         println!("TLS backend: {}", BuildInfo::tls_backend());
+        println!("JSON backend: {}", BuildInfo::json_backend());
 
         #[cfg(feature = "encryption")]
         println!("Encryption backend: {}", BuildInfo::encryption_backend());
@@ -0,0 +1,84 @@
+//! Request and event metrics emitted through the `metrics` facade and Prometheus collectors
+//!
+//! Every function is a no-op unless the `metrics` or `prometheus` feature is enabled.
+
+use crate::Config;
+use std::time::Duration;
+
+/// Records a completed HTTP attempt
+#[cfg(any(feature = "metrics", feature = "prometheus"))]
+#[cfg_attr(not(feature = "prometheus"), allow(unused_variables))]
+pub(crate) fn record_request(
+    config: &Config,
+    method: &str,
+    status: Option<u16>,
+    duration: Duration,
+) {
+    if config.is_dry_run() {
+        return;
+    }
+    #[cfg(feature = "metrics")]
+    {
+        let status = status.map_or_else(|| "error".to_string(), |s| s.to_string());
+        ::metrics::counter!(
+            "pusher_requests_total",
+            "method" => method.to_string(),
+            "status" => status
+        )
+        .increment(1);
+        ::metrics::histogram!("pusher_request_duration_seconds", "method" => method.to_string())
+            .record(duration.as_secs_f64());
+    }
+    #[cfg(feature = "prometheus")]
+    if let Some(metrics) = config.prometheus_metrics() {
+        metrics.record_request(method, status, duration);
+    }
+}
+
+/// Records a retried HTTP attempt
+#[cfg(any(feature = "metrics", feature = "prometheus"))]
+#[cfg_attr(not(feature = "prometheus"), allow(unused_variables))]
+pub(crate) fn record_retry(config: &Config, method: &str) {
+    if config.is_dry_run() {
+        return;
+    }
+    #[cfg(feature = "metrics")]
+    ::metrics::counter!("pusher_retries_total", "method" => method.to_string()).increment(1);
+    #[cfg(feature = "prometheus")]
+    if let Some(metrics) = config.prometheus_metrics() {
+        metrics.record_retry(method);
+    }
+}
+
+/// Records successfully triggered events and the size of their data
+#[cfg(any(feature = "metrics", feature = "prometheus"))]
+#[cfg_attr(not(feature = "prometheus"), allow(unused_variables))]
+pub(crate) fn record_events_triggered(config: &Config, count: usize, bytes: usize) {
+    if config.is_dry_run() {
+        return;
+    }
+    #[cfg(feature = "metrics")]
+    ::metrics::counter!("pusher_events_triggered_total").increment(count as u64);
+    #[cfg(feature = "prometheus")]
+    if let Some(metrics) = config.prometheus_metrics() {
+        metrics.record_events_triggered(count, bytes);
+    }
+}
+
+/// Stub function when metrics are disabled
+#[cfg(not(any(feature = "metrics", feature = "prometheus")))]
+pub(crate) fn record_request(
+    _config: &Config,
+    _method: &str,
+    _status: Option<u16>,
+    _duration: Duration,
+) {
+}
+
+/// Stub function when metrics are disabled
+#[cfg(not(any(feature = "metrics", feature = "prometheus")))]
+pub(crate) fn record_retry(_config: &Config, _method: &str) {}
+
+/// Stub function when metrics are disabled
+#[cfg(not(any(feature = "metrics", feature = "prometheus")))]
+pub(crate) fn record_events_triggered(_config: &Config, _count: usize, _bytes: usize) {}
@@ -0,0 +1,192 @@
+//! Prometheus collectors for request and event metrics
+//!
+//! Unlike the `metrics` feature, which emits through a global recorder, these collectors are
+//! registered into a registry you own and attached to a client with
+//! [`ConfigBuilder::prometheus_metrics`](crate::ConfigBuilder::prometheus_metrics):
+//!
+//! ```ignore
+//! let registry = prometheus::Registry::new();
+//! let metrics = PrometheusMetrics::register(&registry)?;
+//! let config = Config::builder()
+//!     .app_id("123")
+//!     .key("key")
+//!     .secret("secret")
+//!     .prometheus_metrics(metrics)
+//!     .build()?;
+//! ```
+//!
+//! Clone the same `PrometheusMetrics` into every client that should report to the registry;
+//! registering twice into one registry fails with a duplicate-collector error.
+
+use crate::{PusherError, Result};
+use ::prometheus::{
+    HistogramOpts, HistogramVec, IntCounter, IntCounterVec, Opts, Registry, core::Collector,
+};
+use std::fmt;
+use std::time::Duration;
+
+/// Client collectors registered into a `prometheus::Registry`
+///
+/// | Metric | Type | Labels |
+/// |--------|------|--------|
+/// | `pusher_requests_total` | counter | `method`, `status` |
+/// | `pusher_request_duration_seconds` | histogram | `method` |
+/// | `pusher_request_failures_total` | counter | `method`, `status` |
+/// | `pusher_retries_total` | counter | `method` |
+/// | `pusher_events_triggered_total` | counter | |
+/// | `pusher_event_bytes_sent_total` | counter | |
+///
+/// `status` is the HTTP status code, or `error` when no response was received. Failures are
+/// attempts that received no response or a non-2xx status.
+#[derive(Clone)]
+pub struct PrometheusMetrics {
+    requests: IntCounterVec,
+    request_duration: HistogramVec,
+    failures: IntCounterVec,
+    retries: IntCounterVec,
+    events_triggered: IntCounter,
+    event_bytes_sent: IntCounter,
+}
+
+impl PrometheusMetrics {
+    /// Creates the collectors and registers them into `registry`
+    pub fn register(registry: &Registry) -> Result<Self> {
+        let metrics = Self {
+            requests: IntCounterVec::new(
+                Opts::new("pusher_requests_total", "HTTP attempts sent to Pusher"),
+                &["method", "status"],
+            )
+            .map_err(prometheus_error)?,
+            request_duration: HistogramVec::new(
+                HistogramOpts::new(
+                    "pusher_request_duration_seconds",
+                    "Duration of HTTP attempts sent to Pusher",
+                ),
+                &["method"],
+            )
+            .map_err(prometheus_error)?,
+            failures: IntCounterVec::new(
+                Opts::new(
+                    "pusher_request_failures_total",
+                    "HTTP attempts that failed or returned a non-2xx status",
+                ),
+                &["method", "status"],
+            )
+            .map_err(prometheus_error)?,
+            retries: IntCounterVec::new(
+                Opts::new("pusher_retries_total", "HTTP attempts that were retried"),
+                &["method"],
+            )
+            .map_err(prometheus_error)?,
+            events_triggered: IntCounter::new(
+                "pusher_events_triggered_total",
+                "Events successfully triggered",
+            )
+            .map_err(prometheus_error)?,
+            event_bytes_sent: IntCounter::new(
+                "pusher_event_bytes_sent_total",
+                "Bytes of event data successfully triggered",
+            )
+            .map_err(prometheus_error)?,
+        };
+
+        let collectors: [Box<dyn Collector>; 6] = [
+            Box::new(metrics.requests.clone()),
+            Box::new(metrics.request_duration.clone()),
+            Box::new(metrics.failures.clone()),
+            Box::new(metrics.retries.clone()),
+            Box::new(metrics.events_triggered.clone()),
+            Box::new(metrics.event_bytes_sent.clone()),
+        ];
+        for collector in collectors {
+            registry.register(collector).map_err(prometheus_error)?;
+        }
+        Ok(metrics)
+    }
+
+    pub(crate) fn record_request(&self, method: &str, status: Option<u16>, duration: Duration) {
+        let status = status.map_or_else(|| "error".to_string(), |s| s.to_string());
+        self.requests.with_label_values(&[method, &status]).inc();
+        self.request_duration
+            .with_label_values(&[method])
+            .observe(duration.as_secs_f64());
+        if !status.starts_with('2') {
+            self.failures.with_label_values(&[method, &status]).inc();
+        }
+    }
+
+    pub(crate) fn record_retry(&self, method: &str) {
+        self.retries.with_label_values(&[method]).inc();
+    }
+
+    pub(crate) fn record_events_triggered(&self, count: usize, bytes: usize) {
+        self.events_triggered.inc_by(count as u64);
+        self.event_bytes_sent.inc_by(bytes as u64);
+    }
+}
+
+impl fmt::Debug for PrometheusMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "PrometheusMetrics")
+    }
+}
+
+fn prometheus_error(error: ::prometheus::Error) -> PusherError {
+    PusherError::Config {
+        message: format!("Failed to register Prometheus metrics: {}", error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(registry: &Registry, name: &str) -> Vec<(Vec<(String, String)>, f64)> {
+        registry
+            .gather()
+            .into_iter()
+            .filter(|family| family.get_name() == name)
+            .flat_map(|family| family.get_metric().to_vec())
+            .map(|metric| {
+                let labels = metric
+                    .get_label()
+                    .iter()
+                    .map(|l| (l.get_name().to_string(), l.get_value().to_string()))
+                    .collect();
+                (labels, metric.get_counter().get_value())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_register_and_record() {
+        let registry = Registry::new();
+        let metrics = PrometheusMetrics::register(&registry).unwrap();
+
+        metrics.record_request("POST", Some(200), Duration::from_millis(5));
+        metrics.record_request("POST", Some(503), Duration::from_millis(5));
+        metrics.record_request("POST", None, Duration::from_millis(5));
+        metrics.record_retry("POST");
+        metrics.record_events_triggered(2, 30);
+
+        let label = |status: &str| {
+            vec![
+                ("method".to_string(), "POST".to_string()),
+                ("status".to_string(), status.to_string()),
+            ]
+        };
+        assert_eq!(sample(&registry, "pusher_requests_total").len(), 3);
+        assert_eq!(
+            sample(&registry, "pusher_request_failures_total"),
+            vec![(label("503"), 1.0), (label("error"), 1.0)]
+        );
+        assert_eq!(sample(&registry, "pusher_retries_total")[0].1, 1.0);
+        assert_eq!(sample(&registry, "pusher_events_triggered_total")[0].1, 2.0);
+        assert_eq!(
+            sample(&registry, "pusher_event_bytes_sent_total")[0].1,
+            30.0
+        );
+
+        assert!(PrometheusMetrics::register(&registry).is_err());
+    }
+}
@@ -0,0 +1,92 @@
+//! Rate-limit information from response headers
+//!
+//! Pusher-compatible servers such as soketi can report the remaining quota on every response.
+//! Reading it lets callers slow down before requests start failing with HTTP 429.
+
+use crate::transport::Response;
+use http::HeaderMap;
+use std::time::Duration;
+
+/// Quota reported by the server in `X-RateLimit-*` (or IETF `RateLimit-*`) and `Retry-After`
+/// headers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RateLimit {
+    /// Requests allowed in the current window
+    pub limit: Option<u64>,
+    /// Requests left in the current window
+    pub remaining: Option<u64>,
+    /// Seconds until the window resets
+    pub reset: Option<u64>,
+    /// Time to wait before retrying, usually sent with HTTP 429
+    pub retry_after: Option<Duration>,
+}
+
+impl RateLimit {
+    /// Reads the rate-limit headers, returning `None` if the server sent none
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let value = |names: &[&str]| {
+            names.iter().find_map(|name| {
+                headers
+                    .get(*name)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.trim().parse::<u64>().ok())
+            })
+        };
+
+        let rate_limit = Self {
+            limit: value(&["x-ratelimit-limit", "ratelimit-limit"]),
+            remaining: value(&["x-ratelimit-remaining", "ratelimit-remaining"]),
+            reset: value(&["x-ratelimit-reset", "ratelimit-reset"]),
+            retry_after: value(&["retry-after"]).map(Duration::from_secs),
+        };
+        (rate_limit != Self::default()).then_some(rate_limit)
+    }
+
+    /// Reads the rate-limit headers of a response
+    pub fn from_response(response: &Response) -> Option<Self> {
+        Self::from_headers(response.headers())
+    }
+
+    /// Whether the quota is used up
+    pub fn is_exhausted(&self) -> bool {
+        self.remaining == Some(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::HeaderValue;
+
+    #[test]
+    fn test_from_headers() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(RateLimit::from_headers(&headers), None);
+
+        headers.insert("X-RateLimit-Limit", HeaderValue::from_static("100"));
+        headers.insert("X-RateLimit-Remaining", HeaderValue::from_static("0"));
+        headers.insert("RateLimit-Reset", HeaderValue::from_static("12"));
+        headers.insert("Retry-After", HeaderValue::from_static("3"));
+        let rate_limit = RateLimit::from_headers(&headers).unwrap();
+        assert_eq!(
+            rate_limit,
+            RateLimit {
+                limit: Some(100),
+                remaining: Some(0),
+                reset: Some(12),
+                retry_after: Some(Duration::from_secs(3)),
+            }
+        );
+        assert!(rate_limit.is_exhausted());
+    }
+
+    #[test]
+    fn test_ignores_unparsable_values() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "Retry-After",
+            HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+        );
+        assert_eq!(RateLimit::from_headers(&headers), None);
+    }
+}
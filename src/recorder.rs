@@ -0,0 +1,330 @@
+//! Capture and replay of sanitized request/response pairs
+//!
+//! A [`DebugRecorder`] appends every HTTP attempt to a JSONL file with auth
+//! signatures and keys stripped and request bodies redacted like audit payloads.
+//! A [`ReplayServer`] serves a recording back over local HTTP so intermittent
+//! failures can be reproduced offline.
+
+use crate::interceptor::RequestParts;
+use crate::{PusherError, Result, json, util};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::Write;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::{Arc, Mutex, mpsc};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+/// A single recorded HTTP attempt
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedExchange {
+    pub method: String,
+    /// Request path and query, with sensitive parameters redacted
+    pub path: String,
+    /// Request body as kept by the audit redaction, if one is configured
+    pub request_body: Option<String>,
+    pub status: Option<u16>,
+    /// Response body, captured for non-success responses only
+    pub response_body: Option<String>,
+    pub error: Option<String>,
+    pub attempt: u32,
+}
+
+/// Appends sanitized exchanges to a JSONL file
+///
+/// Lines are written by a background thread, so recording never blocks the async runtime.
+/// Dropping the recorder waits for pending lines to be written.
+pub struct DebugRecorder {
+    sender: mpsc::Sender<Command>,
+}
+
+enum Command {
+    Write(String),
+    Flush(mpsc::Sender<()>),
+}
+
+impl DebugRecorder {
+    /// Creates a recorder appending to the file at `path`
+    pub fn create(path: impl AsRef<Path>) -> Result<Self> {
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path.as_ref())
+            .map_err(|e| PusherError::Config {
+                message: format!(
+                    "Failed to open recording file '{}': {}",
+                    path.as_ref().display(),
+                    e
+                ),
+            })?;
+
+        let (sender, receiver) = mpsc::channel();
+        std::thread::Builder::new()
+            .name("pushers-recorder".to_string())
+            .spawn(move || write_lines(file, receiver))
+            .map_err(|e| PusherError::Config {
+                message: format!("Failed to start recording thread: {}", e),
+            })?;
+
+        Ok(Self { sender })
+    }
+
+    /// Blocks until every exchange recorded so far has been written
+    pub fn flush(&self) {
+        let (done, wait) = mpsc::channel();
+        if self.sender.send(Command::Flush(done)).is_ok() {
+            let _ = wait.recv();
+        }
+    }
+
+    /// Records an attempt, ignoring write failures so recording never breaks requests
+    pub(crate) fn record(
+        &self,
+        request: &RequestParts,
+        request_body: Option<String>,
+        status: Option<u16>,
+        response_body: Option<&str>,
+        error: Option<String>,
+    ) {
+        let exchange = RecordedExchange {
+            method: request.method.clone(),
+            path: sanitize_url(&request.url),
+            request_body,
+            status,
+            response_body: response_body.map(str::to_string),
+            error,
+            attempt: request.attempt,
+        };
+
+        if let Ok(line) = json::to_string(&exchange) {
+            let _ = self.sender.send(Command::Write(line));
+        }
+    }
+}
+
+impl Drop for DebugRecorder {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+/// Writes recorded lines until the recorder is dropped
+fn write_lines(mut file: std::fs::File, receiver: mpsc::Receiver<Command>) {
+    for command in receiver {
+        match command {
+            Command::Write(line) => {
+                let _ = writeln!(file, "{}", line);
+            }
+            Command::Flush(done) => {
+                let _ = file.flush();
+                let _ = done.send(());
+            }
+        }
+    }
+}
+
+impl fmt::Debug for DebugRecorder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DebugRecorder")
+    }
+}
+
+/// Strips the host and sensitive query parameters from a request URL
+pub fn sanitize_url(raw: &str) -> String {
+    let redacted = util::redact_signed_url(raw);
+    let Ok(parsed) = url::Url::parse(&redacted) else {
+        return "[INVALID URL]".to_string();
+    };
+
+    match parsed.query() {
+        Some(query) => format!("{}?{}", parsed.path(), query),
+        None => parsed.path().to_string(),
+    }
+}
+
+/// Loads the exchanges from a JSONL recording
+pub fn load_recording(path: impl AsRef<Path>) -> Result<Vec<RecordedExchange>> {
+    let contents = std::fs::read_to_string(path.as_ref()).map_err(|e| PusherError::Config {
+        message: format!(
+            "Failed to read recording file '{}': {}",
+            path.as_ref().display(),
+            e
+        ),
+    })?;
+
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| Ok(json::from_str(line)?))
+        .collect()
+}
+
+/// Local HTTP server answering requests with recorded responses, in order
+#[derive(Debug)]
+pub struct ReplayServer {
+    addr: SocketAddr,
+    handle: JoinHandle<()>,
+}
+
+impl ReplayServer {
+    /// Starts serving the given exchanges on an ephemeral localhost port
+    pub async fn start(exchanges: Vec<RecordedExchange>) -> Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .map_err(|e| PusherError::Config {
+                message: format!("Failed to bind replay server: {}", e),
+            })?;
+        let addr = listener.local_addr().map_err(|e| PusherError::Config {
+            message: format!("Failed to bind replay server: {}", e),
+        })?;
+
+        let queue = Arc::new(Mutex::new(VecDeque::from(exchanges)));
+        let handle = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let next = queue.lock().ok().and_then(|mut q| q.pop_front());
+                tokio::spawn(serve_exchange(stream, next));
+            }
+        });
+
+        Ok(Self { addr, handle })
+    }
+
+    /// Starts serving the exchanges stored in a JSONL recording
+    pub async fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        Self::start(load_recording(path)?).await
+    }
+
+    /// Host to point the client configuration at
+    pub fn host(&self) -> String {
+        self.addr.ip().to_string()
+    }
+
+    /// Port to point the client configuration at
+    pub fn port(&self) -> u16 {
+        self.addr.port()
+    }
+}
+
+impl Drop for ReplayServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+/// Reads one HTTP request and answers it with the recorded response
+async fn serve_exchange(mut stream: TcpStream, exchange: Option<RecordedExchange>) {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    loop {
+        let Ok(n) = stream.read(&mut chunk).await else {
+            return;
+        };
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        if request_complete(&buf) {
+            break;
+        }
+    }
+
+    let (status, body) = match exchange {
+        // Network errors are replayed by dropping the connection
+        Some(RecordedExchange { status: None, .. }) => return,
+        Some(exchange) => (
+            exchange.status.unwrap_or(200),
+            exchange.response_body.unwrap_or_else(|| "{}".to_string()),
+        ),
+        None => (500, "No recorded exchange left to replay".to_string()),
+    };
+
+    let response = format!(
+        "HTTP/1.1 {} Replayed\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+/// Checks whether the headers and the full `Content-Length` body have been read
+fn request_complete(buf: &[u8]) -> bool {
+    let Some(header_end) = buf.windows(4).position(|w| w == b"\r\n\r\n") else {
+        return false;
+    };
+
+    let headers = String::from_utf8_lossy(&buf[..header_end]);
+    let content_length = headers
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+        .unwrap_or(0);
+
+    buf.len() >= header_end + 4 + content_length
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Config, Pusher, json::json};
+
+    #[test]
+    fn test_sanitize_url() {
+        let url = "https://api.pusherapp.com/apps/1/events?auth_key=key&auth_timestamp=1&auth_signature=abc";
+        assert_eq!(
+            sanitize_url(url),
+            "/apps/1/events?auth_key=[REDACTED]&auth_timestamp=1&auth_signature=[REDACTED]"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_record_and_replay() {
+        let path =
+            std::env::temp_dir().join(format!("pushers-recording-{}.jsonl", rand::random::<u64>()));
+
+        let server = ReplayServer::start(vec![RecordedExchange {
+            method: "POST".to_string(),
+            path: "/apps/123/events".to_string(),
+            request_body: None,
+            status: Some(400),
+            response_body: Some("bad request".to_string()),
+            error: None,
+            attempt: 1,
+        }])
+        .await
+        .unwrap();
+
+        let config = Config::builder()
+            .app_id("123")
+            .key("key")
+            .secret("secret")
+            .host(server.host())
+            .port(server.port())
+            .use_tls(false)
+            .debug_recorder(DebugRecorder::create(&path).unwrap())
+            .audit_redaction(|payload| Some(format!("{} bytes", payload.len())))
+            .build()
+            .unwrap();
+        let pusher = Pusher::new(config).unwrap();
+
+        match pusher.post("/events", &json!({})).await {
+            Err(PusherError::Request(err)) => assert_eq!(err.status, Some(400)),
+            other => panic!("Expected request error, got {:?}", other),
+        }
+
+        pusher.config().debug_recorder().unwrap().flush();
+        let recording = load_recording(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(recording.len(), 1);
+        assert_eq!(recording[0].status, Some(400));
+        assert_eq!(recording[0].request_body.as_deref(), Some("2 bytes"));
+        assert_eq!(recording[0].response_body.as_deref(), Some("bad request"));
+        assert!(recording[0].path.contains("auth_signature=[REDACTED]"));
+    }
+}
@@ -0,0 +1,293 @@
+//! Server-side verification of signed Pusher REST API requests
+//!
+//! Useful when implementing a Pusher-compatible server: the checks mirror how this crate
+//! signs outgoing requests.
+
+use crate::clock::{Clock, ClockHandle};
+use crate::digest::{BodyDigest, DigestHandle};
+use crate::errors::RequestVerificationError;
+use crate::{Token, signing, util};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Default allowed difference between `auth_timestamp` and the local clock
+pub const DEFAULT_TIMESTAMP_TOLERANCE: Duration = Duration::from_secs(600);
+
+/// Default limit on how far in the future a signed `auth_expires` may lie
+pub const DEFAULT_MAX_EXPIRY: Duration = Duration::from_secs(3600);
+
+/// Verifies `auth_key`, `auth_timestamp`, `auth_version`, `body_md5`, and `auth_signature`
+/// of incoming REST requests
+///
+/// Requests carrying a signed [`auth_expires`](signing::EXPIRES_PARAM) are accepted until that
+/// time, as long as `auth_timestamp` is not further in the future than the tolerance and the
+/// expiry is at most [`max_expiry`](RequestVerifier::max_expiry) away.
+#[derive(Debug, Clone)]
+pub struct RequestVerifier {
+    tokens: Vec<Token>,
+    tolerance: Duration,
+    max_expiry: Duration,
+    clock: ClockHandle,
+    digest: DigestHandle,
+}
+
+impl RequestVerifier {
+    /// Creates a verifier for requests signed with the given token
+    ///
+    /// Fails if the token has no secret, or if the `md5` feature is disabled.
+    pub fn new(token: Token) -> crate::Result<Self> {
+        Self::with_tokens(vec![token])
+    }
+
+    /// Creates a verifier accepting any of the given tokens, matched by `auth_key`
+    ///
+    /// Fails if any token has no secret, or if the `md5` feature is disabled; use
+    /// [`RequestVerifier::with_digest`] to supply a provider then.
+    pub fn with_tokens(tokens: Vec<Token>) -> crate::Result<Self> {
+        Self::with_digest(tokens, DigestHandle::builtin()?.0)
+    }
+
+    /// Creates a verifier accepting any of the given tokens, checking `body_md5` with the
+    /// given provider
+    ///
+    /// Fails if any token has no secret.
+    pub fn with_digest(tokens: Vec<Token>, digest: Arc<dyn BodyDigest>) -> crate::Result<Self> {
+        for token in &tokens {
+            token.require_secret()?;
+        }
+        Ok(Self {
+            tokens,
+            tolerance: DEFAULT_TIMESTAMP_TOLERANCE,
+            max_expiry: DEFAULT_MAX_EXPIRY,
+            clock: ClockHandle::default(),
+            digest: DigestHandle(digest),
+        })
+    }
+
+    /// Sets the allowed clock skew for `auth_timestamp`
+    pub fn tolerance(mut self, tolerance: Duration) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Sets how far in the future a signed `auth_expires` may lie
+    ///
+    /// Longer-lived pre-signed requests are rejected, so a leaked URL stays usable for at most
+    /// this long.
+    pub fn max_expiry(mut self, max_expiry: Duration) -> Self {
+        self.max_expiry = max_expiry;
+        self
+    }
+
+    /// Sets the clock used for the timestamp check
+    pub fn clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = ClockHandle(clock);
+        self
+    }
+
+    /// Sets the provider used to check `body_md5`
+    pub fn body_digest(mut self, digest: Arc<dyn BodyDigest>) -> Self {
+        self.digest = DigestHandle(digest);
+        self
+    }
+
+    /// Verifies a request given its method, path, raw (undecoded) query string, and body
+    pub fn verify(
+        &self,
+        method: &str,
+        path: &str,
+        query: &str,
+        body: Option<&[u8]>,
+    ) -> Result<(), RequestVerificationError> {
+        let mut params = signing::parse_query_string(query);
+
+        let signature = params.remove(signing::SIGNATURE_PARAM).ok_or(
+            RequestVerificationError::MissingParameter(signing::SIGNATURE_PARAM),
+        )?;
+        let param = |name: &'static str| {
+            params
+                .get(name)
+                .ok_or(RequestVerificationError::MissingParameter(name))
+        };
+
+        let key = param("auth_key")?;
+        let token = self
+            .tokens
+            .iter()
+            .find(|token| util::secure_compare(&token.key, key))
+            .ok_or_else(|| RequestVerificationError::UnknownKey(key.clone()))?;
+
+        let version = param("auth_version")?;
+        if version != signing::AUTH_VERSION {
+            return Err(RequestVerificationError::UnsupportedVersion(
+                version.clone(),
+            ));
+        }
+
+        let timestamp = param("auth_timestamp")?
+            .parse::<u64>()
+            .map_err(|_| RequestVerificationError::InvalidTimestamp)?;
+        let now = self.clock.unix_secs();
+        match params.get(signing::EXPIRES_PARAM) {
+            Some(expires) => {
+                let expires = expires
+                    .parse::<u64>()
+                    .map_err(|_| RequestVerificationError::InvalidExpiry)?;
+                if now > expires {
+                    return Err(RequestVerificationError::Expired { expires });
+                }
+                if timestamp > now.saturating_add(self.tolerance.as_secs()) {
+                    return Err(RequestVerificationError::TimestampOutsideTolerance { timestamp });
+                }
+                if expires > now.saturating_add(self.max_expiry.as_secs()) {
+                    return Err(RequestVerificationError::ExpiryTooFar { expires });
+                }
+            }
+            None if !signing::timestamp_within(timestamp, now, self.tolerance) => {
+                return Err(RequestVerificationError::TimestampOutsideTolerance { timestamp });
+            }
+            None => {}
+        }
+
+        if let Some(body) = body.filter(|b| !b.is_empty()) {
+            let expected = self.digest.md5_hex(body);
+            let body_md5 = param("body_md5")?;
+            if !util::secure_compare(&expected, body_md5) {
+                return Err(RequestVerificationError::BodyMd5Mismatch);
+            }
+        }
+
+        let sign_data = signing::string_to_sign(method, path, &params);
+        if !token.verify(&sign_data, &signature) {
+            return Err(RequestVerificationError::SignatureMismatch);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Pusher;
+    use crate::clock::FixedClock;
+    use crate::config::Config;
+
+    fn signed_query(body: &str) -> String {
+        let config = Config::builder()
+            .app_id("123")
+            .key("key")
+            .secret("secret")
+            .clock(FixedClock::from_unix_secs(1_700_000_000))
+            .build()
+            .unwrap();
+        let pusher = Pusher::new(config).unwrap();
+        pusher.create_signed_query_string("POST", "/apps/123/events", Some(body), None)
+    }
+
+    #[test]
+    fn test_verify_signed_request() {
+        let body = r#"{"name":"e"}"#;
+        let query = signed_query(body);
+        let verifier = RequestVerifier::new(Token::new("key", "secret"))
+            .unwrap()
+            .clock(Arc::new(FixedClock::from_unix_secs(1_700_000_030)));
+
+        assert!(
+            verifier
+                .verify("POST", "/apps/123/events", &query, Some(body.as_bytes()))
+                .is_ok()
+        );
+        assert_eq!(
+            verifier.verify("POST", "/apps/123/events", &query, Some(b"tampered")),
+            Err(RequestVerificationError::BodyMd5Mismatch)
+        );
+        assert_eq!(
+            verifier.verify("POST", "/apps/123/other", &query, Some(body.as_bytes())),
+            Err(RequestVerificationError::SignatureMismatch)
+        );
+
+        assert!(RequestVerifier::with_tokens(vec![Token::new("key", "")]).is_err());
+
+        let late = verifier
+            .clone()
+            .clock(Arc::new(FixedClock::from_unix_secs(1_700_001_000)));
+        assert_eq!(
+            late.verify("POST", "/apps/123/events", &query, Some(body.as_bytes())),
+            Err(RequestVerificationError::TimestampOutsideTolerance {
+                timestamp: 1_700_000_000
+            })
+        );
+    }
+
+    #[test]
+    fn test_verify_pre_signed_request() {
+        let pusher = Pusher::new(
+            Config::builder()
+                .app_id("123")
+                .key("key")
+                .secret("secret")
+                .build()
+                .unwrap(),
+        )
+        .unwrap();
+        let query = pusher
+            .create_signed_query_string_at(
+                "GET",
+                "/apps/123/channels",
+                None,
+                None,
+                1_700_000_000,
+                Some(1_700_086_400),
+            )
+            .unwrap();
+        assert!(query.contains("auth_timestamp=1700000000"));
+        assert!(query.contains("auth_expires=1700086400"));
+
+        let verifier_at = |now| {
+            RequestVerifier::new(Token::new("key", "secret"))
+                .unwrap()
+                .max_expiry(Duration::from_secs(86_400))
+                .clock(Arc::new(FixedClock::from_unix_secs(now)))
+        };
+        assert!(
+            verifier_at(1_700_080_000)
+                .verify("GET", "/apps/123/channels", &query, None)
+                .is_ok()
+        );
+        assert_eq!(
+            verifier_at(1_700_090_000).verify("GET", "/apps/123/channels", &query, None),
+            Err(RequestVerificationError::Expired {
+                expires: 1_700_086_400
+            })
+        );
+        assert_eq!(
+            verifier_at(1_699_000_000).verify("GET", "/apps/123/channels", &query, None),
+            Err(RequestVerificationError::TimestampOutsideTolerance {
+                timestamp: 1_700_000_000
+            })
+        );
+
+        // Expiries further away than max_expiry are rejected, even when correctly signed
+        assert_eq!(
+            verifier_at(1_700_000_000)
+                .max_expiry(DEFAULT_MAX_EXPIRY)
+                .verify("GET", "/apps/123/channels", &query, None),
+            Err(RequestVerificationError::ExpiryTooFar {
+                expires: 1_700_086_400
+            })
+        );
+        assert!(
+            verifier_at(1_700_083_000)
+                .max_expiry(DEFAULT_MAX_EXPIRY)
+                .verify("GET", "/apps/123/channels", &query, None)
+                .is_ok()
+        );
+
+        let tampered = query.replace("auth_expires=1700086400", "auth_expires=1700090000");
+        assert_eq!(
+            verifier_at(1_700_080_000).verify("GET", "/apps/123/channels", &tampered, None),
+            Err(RequestVerificationError::SignatureMismatch)
+        );
+    }
+}
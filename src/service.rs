@@ -0,0 +1,94 @@
+//! `tower::Service` implementation for triggering events
+//!
+//! Lets tower middleware (rate limiting, retries, load shedding, timeouts) wrap event
+//! publication:
+//!
+//! ```ignore
+//! let service = tower::ServiceBuilder::new()
+//!     .rate_limit(100, Duration::from_secs(1))
+//!     .service(pusher.clone());
+//! ```
+
+use crate::events::{EventData, TriggerParams};
+use crate::interceptor::BoxFuture;
+use crate::transport::Response;
+use crate::{Channel, Pusher, PusherError, Result};
+use std::task::{Context, Poll};
+
+/// A single event trigger, as accepted by the `Service` implementation for [`Pusher`]
+#[derive(Debug, Clone)]
+pub struct TriggerRequest {
+    pub channels: Vec<Channel>,
+    pub event: String,
+    pub data: EventData,
+    pub params: Option<TriggerParams>,
+}
+
+impl TriggerRequest {
+    pub fn new(
+        channels: impl IntoIterator<Item = Channel>,
+        event: impl Into<String>,
+        data: impl Into<EventData>,
+    ) -> Self {
+        Self {
+            channels: channels.into_iter().collect(),
+            event: event.into(),
+            data: data.into(),
+            params: None,
+        }
+    }
+
+    /// Sets the trigger parameters (socket exclusion, info, tags)
+    pub fn with_params(mut self, params: TriggerParams) -> Self {
+        self.params = Some(params);
+        self
+    }
+}
+
+impl tower_service::Service<TriggerRequest> for Pusher {
+    type Response = Response;
+    type Error = PusherError;
+    type Future = BoxFuture<'static, Result<Response>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: TriggerRequest) -> Self::Future {
+        let pusher = self.clone();
+        Box::pin(async move {
+            pusher
+                .trigger(
+                    request.channels,
+                    &request.event,
+                    request.data,
+                    request.params,
+                )
+                .await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_service_validates_request() {
+        let pusher = Pusher::new(Config::new("123", "key", "secret")).unwrap();
+
+        let result = pusher
+            .clone()
+            .oneshot(TriggerRequest::new(Vec::new(), "created", "{}"))
+            .await;
+        assert!(matches!(result, Err(PusherError::Validation { .. })));
+
+        let channel = Channel::from_string("orders").unwrap();
+        let result = pusher
+            .oneshot(TriggerRequest::new([channel], "e".repeat(201), "{}"))
+            .await;
+        assert!(matches!(result, Err(PusherError::Validation { .. })));
+    }
+}
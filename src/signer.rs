@@ -0,0 +1,291 @@
+//! Pluggable HMAC signing for request and auth signatures
+
+use crate::clock::{Clock, ClockHandle};
+use crate::digest::{BodyDigest, DigestHandle};
+use crate::interceptor::BoxFuture;
+use crate::{Result, Token, signing};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// Computes Pusher signatures, possibly without the app secret in process memory
+///
+/// Implementations must return the lowercase hex HMAC-SHA256 of `data` keyed with the app
+/// secret, e.g. by delegating to AWS KMS or Vault Transit. [`Token`] is the in-memory default.
+pub trait Signer: Send + Sync {
+    /// Signs the data
+    fn sign<'a>(&'a self, data: &'a [u8]) -> BoxFuture<'a, Result<String>>;
+}
+
+impl Signer for Token {
+    fn sign<'a>(&'a self, data: &'a [u8]) -> BoxFuture<'a, Result<String>> {
+        let signature = hex::encode(self.sign_bytes(data));
+        Box::pin(async move { Ok(signature) })
+    }
+}
+
+/// Shared handle to the signer stored in the configuration
+#[derive(Clone)]
+pub(crate) struct SignerHandle(pub(crate) Arc<dyn Signer>);
+
+impl fmt::Debug for SignerHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Signer")
+    }
+}
+
+/// Produces signed query strings for Pusher REST API requests without a client
+///
+/// Useful when requests are sent through another HTTP stack, such as an edge proxy.
+#[derive(Debug, Clone)]
+pub struct RequestSigner {
+    token: Token,
+    clock: ClockHandle,
+    digest: DigestHandle,
+}
+
+impl RequestSigner {
+    /// Creates a signer for the given token
+    ///
+    /// Fails if the token has no secret, or if the `md5` feature is disabled; use
+    /// [`RequestSigner::with_digest`] to supply a provider then.
+    pub fn new(token: Token) -> Result<Self> {
+        Self::with_digest(token, DigestHandle::builtin()?.0)
+    }
+
+    /// Creates a signer that computes `body_md5` with the given provider
+    ///
+    /// Fails if the token has no secret.
+    pub fn with_digest(token: Token, digest: Arc<dyn BodyDigest>) -> Result<Self> {
+        token.require_secret()?;
+        Ok(Self {
+            token,
+            clock: ClockHandle::default(),
+            digest: DigestHandle(digest),
+        })
+    }
+
+    /// Sets the clock used for `auth_timestamp`
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = ClockHandle(clock);
+        self
+    }
+
+    /// Sets the provider used to compute `body_md5`
+    pub fn with_body_digest(mut self, digest: Arc<dyn BodyDigest>) -> Self {
+        self.digest = DigestHandle(digest);
+        self
+    }
+
+    /// Returns the signed query string (including `auth_signature`) for a request
+    ///
+    /// `path` is the full request path, e.g. `/apps/123/events`.
+    pub fn sign(
+        &self,
+        method: &str,
+        path: &str,
+        params: Option<&BTreeMap<String, String>>,
+        body: Option<&str>,
+    ) -> String {
+        self.sign_at(method, path, params, body, self.clock.unix_secs())
+    }
+
+    /// Like [`sign`](Self::sign), with an explicit `auth_timestamp` in Unix seconds
+    pub fn sign_at(
+        &self,
+        method: &str,
+        path: &str,
+        params: Option<&BTreeMap<String, String>>,
+        body: Option<&str>,
+        timestamp: u64,
+    ) -> String {
+        create_signed_query_string(
+            &self.token,
+            &self.digest,
+            timestamp,
+            method,
+            path,
+            body,
+            params,
+        )
+    }
+
+    /// Signs an `http::Request` in place, replacing its query string with the signed one
+    ///
+    /// Existing query parameters are kept and included in the signature.
+    #[cfg(feature = "http")]
+    pub fn sign_request<B: AsRef<[u8]>>(&self, request: &mut http::Request<B>) -> Result<()> {
+        let invalid = |message: String| crate::PusherError::Validation { message };
+
+        let params = signing::parse_query_string(request.uri().query().unwrap_or_default());
+        let body = request.body().as_ref();
+        let body = if body.is_empty() {
+            None
+        } else {
+            Some(
+                std::str::from_utf8(body)
+                    .map_err(|_| invalid("Request body is not valid UTF-8".to_string()))?,
+            )
+        };
+
+        let path = request.uri().path().to_string();
+        let query = self.sign(request.method().as_str(), &path, Some(&params), body);
+
+        let mut parts = request.uri().clone().into_parts();
+        parts.path_and_query = Some(
+            format!("{}?{}", path, query)
+                .parse()
+                .map_err(|e| invalid(format!("Invalid signed URI: {}", e)))?,
+        );
+        *request.uri_mut() = http::Uri::from_parts(parts)
+            .map_err(|e| invalid(format!("Invalid signed URI: {}", e)))?;
+        Ok(())
+    }
+}
+
+/// Creates a signed query string for Pusher API requests
+pub(crate) fn create_signed_query_string(
+    token: &Token,
+    digest: &DigestHandle,
+    timestamp: u64,
+    method: &str,
+    path: &str,
+    body: Option<&str>,
+    params: Option<&BTreeMap<String, String>>,
+) -> String {
+    let (query_string, sign_data) =
+        unsigned_query_string(&token.key, digest, timestamp, method, path, body, params);
+    let signature = token.sign(&sign_data);
+
+    format!("{}&auth_signature={}", query_string, signature)
+}
+
+/// Builds the percent-encoded auth query string (without signature) and the data to sign
+pub(crate) fn unsigned_query_string(
+    key: &str,
+    digest: &DigestHandle,
+    timestamp: u64,
+    method: &str,
+    path: &str,
+    body: Option<&str>,
+    params: Option<&BTreeMap<String, String>>,
+) -> (String, String) {
+    let body_md5 = body.map(|body| digest.md5_hex(body.as_bytes()));
+    let mut query_params = signing::auth_params(key, timestamp, body_md5.as_deref());
+
+    if let Some(params) = params {
+        for (key, value) in params {
+            query_params.insert(key.clone(), value.clone());
+        }
+    }
+
+    // Pusher signs the raw values; only the transmitted query string is percent-encoded
+    let sign_data = signing::string_to_sign(method, path, &query_params);
+    (signing::encode_query_string(&query_params), sign_data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_token_signer_matches_sign() {
+        let token = Token::new("key", "secret");
+        let signature = Signer::sign(&token, b"data").await.unwrap();
+        assert_eq!(signature, token.sign("data"));
+    }
+
+    #[test]
+    fn test_request_signer_custom_digest() {
+        struct ZeroDigest;
+        impl BodyDigest for ZeroDigest {
+            fn md5(&self, _body: &[u8]) -> [u8; 16] {
+                [0; 16]
+            }
+        }
+
+        let signer =
+            RequestSigner::with_digest(Token::new("key", "secret"), Arc::new(ZeroDigest)).unwrap();
+        let query = signer.sign("POST", "/apps/123/events", None, Some("{}"));
+
+        assert!(query.contains(&format!("body_md5={}", "0".repeat(32))));
+    }
+
+    #[cfg(not(feature = "md5"))]
+    #[test]
+    fn test_missing_digest_fails_at_construction() {
+        let token = Token::new("key", "secret");
+        assert!(matches!(
+            RequestSigner::new(token.clone()),
+            Err(crate::PusherError::Config { .. })
+        ));
+        assert!(matches!(
+            crate::server::RequestVerifier::new(token),
+            Err(crate::PusherError::Config { .. })
+        ));
+    }
+
+    #[cfg(feature = "md5")]
+    #[test]
+    fn test_request_signer() {
+        let signer = RequestSigner::new(Token::new("key", "secret"))
+            .unwrap()
+            .with_clock(Arc::new(crate::clock::FixedClock::from_unix_secs(
+                1_700_000_000,
+            )));
+        let query = signer.sign("POST", "/apps/123/events", None, Some("{}"));
+
+        assert!(
+            query.starts_with("auth_key=key&auth_timestamp=1700000000&auth_version=1.0&body_md5=")
+        );
+        assert!(
+            crate::server::RequestVerifier::new(Token::new("key", "secret"))
+                .unwrap()
+                .clock(Arc::new(crate::clock::FixedClock::from_unix_secs(
+                    1_700_000_000
+                )))
+                .verify("POST", "/apps/123/events", &query, Some(b"{}"))
+                .is_ok()
+        );
+    }
+
+    #[cfg(feature = "md5")]
+    #[test]
+    fn test_params_encoded_but_signed_raw() {
+        let token = Token::new("key", "secret");
+        let mut params = BTreeMap::new();
+        params.insert(
+            "filter_by_prefix".to_string(),
+            "presence-room#1".to_string(),
+        );
+        params.insert("info".to_string(), "a&b=c d+%ü".to_string());
+
+        let query = create_signed_query_string(
+            &token,
+            &DigestHandle::builtin().unwrap(),
+            1_700_000_000,
+            "GET",
+            "/apps/123/channels",
+            None,
+            Some(&params),
+        );
+        assert!(query.contains("filter_by_prefix=presence-room%231"));
+        assert!(query.contains("info=a%26b%3Dc%20d%2B%25%C3%BC"));
+
+        let expected = token.sign(
+            "GET\n/apps/123/channels\nauth_key=key&auth_timestamp=1700000000&auth_version=1.0\
+             &filter_by_prefix=presence-room#1&info=a&b=c d+%ü",
+        );
+        assert!(query.ends_with(&format!("&auth_signature={}", expected)));
+        assert!(RequestSigner::new(Token::new("key", "")).is_err());
+        assert!(
+            crate::server::RequestVerifier::new(token)
+                .unwrap()
+                .clock(Arc::new(crate::clock::FixedClock::from_unix_secs(
+                    1_700_000_000
+                )))
+                .verify("GET", "/apps/123/channels", &query, None)
+                .is_ok()
+        );
+    }
+}
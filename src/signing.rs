@@ -0,0 +1,150 @@
+//! Primitives of the Pusher REST API request signature
+//!
+//! A request is signed by adding `auth_key`, `auth_timestamp`, `auth_version`, and (for
+//! requests with a body) `body_md5` to its query parameters, then appending
+//! `auth_signature`: the hex HMAC-SHA256, keyed with the app secret, of
+//!
+//! ```text
+//! METHOD\npath\nkey1=value1&key2=value2
+//! ```
+//!
+//! where the parameters are sorted by key, values are not percent-encoded, and
+//! `auth_signature` itself is excluded. [`RequestSigner`](crate::RequestSigner) and
+//! [`RequestVerifier`](crate::server::RequestVerifier) are built on these functions; use them
+//! directly when a proxy or another language's client must produce identical signatures.
+
+use crate::digest::BodyDigest;
+use crate::{Token, util};
+use std::collections::BTreeMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The `auth_version` this crate produces and accepts
+pub const AUTH_VERSION: &str = "1.0";
+
+/// Name of the query parameter holding the signature
+pub const SIGNATURE_PARAM: &str = "auth_signature";
+
+/// Optional signed parameter with the Unix time after which a pre-signed request is rejected
+///
+/// Pusher's hosted API ignores it; [`RequestVerifier`](crate::server::RequestVerifier) accepts
+/// requests carrying it until that time instead of applying its timestamp tolerance.
+pub const EXPIRES_PARAM: &str = "auth_expires";
+
+/// Current time as an `auth_timestamp`, in seconds since the Unix epoch
+pub fn auth_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Whether `timestamp` is within `tolerance` of `now`, in either direction
+pub fn timestamp_within(timestamp: u64, now: u64, tolerance: Duration) -> bool {
+    now.abs_diff(timestamp) <= tolerance.as_secs()
+}
+
+/// Lowercase hex MD5 of a request body, as sent in `body_md5`
+#[cfg(feature = "md5")]
+#[cfg_attr(docsrs, doc(cfg(feature = "md5")))]
+pub fn body_md5(body: &[u8]) -> String {
+    body_md5_with(&crate::digest::Md5Digest, body)
+}
+
+/// Lowercase hex MD5 of a request body computed by a custom provider
+pub fn body_md5_with(digest: &dyn BodyDigest, body: &[u8]) -> String {
+    hex::encode(digest.md5(body))
+}
+
+/// The `auth_*` and `body_md5` parameters of a request, without the signature
+pub fn auth_params(key: &str, timestamp: u64, body_md5: Option<&str>) -> BTreeMap<String, String> {
+    let mut params = BTreeMap::new();
+    params.insert("auth_key".to_string(), key.to_string());
+    params.insert("auth_timestamp".to_string(), timestamp.to_string());
+    params.insert("auth_version".to_string(), AUTH_VERSION.to_string());
+    if let Some(body_md5) = body_md5 {
+        params.insert("body_md5".to_string(), body_md5.to_string());
+    }
+    params
+}
+
+/// Sorted `key=value` pairs with raw values, excluding `auth_signature`
+pub fn canonical_query_string(params: &BTreeMap<String, String>) -> String {
+    params
+        .iter()
+        .filter(|(key, _)| key.as_str() != SIGNATURE_PARAM)
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// The string whose HMAC is the request signature
+pub fn string_to_sign(method: &str, path: &str, params: &BTreeMap<String, String>) -> String {
+    format!(
+        "{}\n{}\n{}",
+        method.to_uppercase(),
+        path,
+        canonical_query_string(params)
+    )
+}
+
+/// Percent-encoded query string for transmission, in key order
+pub fn encode_query_string(params: &BTreeMap<String, String>) -> String {
+    util::to_encoded_query_string(params)
+}
+
+/// Decodes a received query string into its parameters
+pub fn parse_query_string(query: &str) -> BTreeMap<String, String> {
+    util::parse_query_string(query)
+}
+
+/// Hex `auth_signature` of a request with the given parameters
+pub fn sign(token: &Token, method: &str, path: &str, params: &BTreeMap<String, String>) -> String {
+    token.sign(&string_to_sign(method, path, params))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_string_to_sign() {
+        let mut params = auth_params("key", 1_700_000_000, Some("abc"));
+        params.insert("info".to_string(), "user_count".to_string());
+        params.insert(SIGNATURE_PARAM.to_string(), "ignored".to_string());
+
+        assert_eq!(
+            string_to_sign("get", "/apps/123/channels", &params),
+            "GET\n/apps/123/channels\nauth_key=key&auth_timestamp=1700000000&auth_version=1.0\
+             &body_md5=abc&info=user_count"
+        );
+    }
+
+    #[cfg(feature = "md5")]
+    #[test]
+    fn test_matches_request_signer() {
+        let token = Token::new("key", "secret");
+        let signer = crate::RequestSigner::new(token.clone())
+            .unwrap()
+            .with_clock(std::sync::Arc::new(
+                crate::clock::FixedClock::from_unix_secs(1_700_000_000),
+            ));
+        let query = signer.sign("POST", "/apps/123/events", None, Some("{}"));
+
+        let mut params = parse_query_string(&query);
+        let signature = params.remove(SIGNATURE_PARAM).unwrap();
+        assert_eq!(
+            params,
+            auth_params("key", 1_700_000_000, Some(&body_md5(b"{}")))
+        );
+        assert_eq!(sign(&token, "POST", "/apps/123/events", &params), signature);
+    }
+
+    #[test]
+    fn test_timestamp_within() {
+        let tolerance = Duration::from_secs(600);
+        assert!(timestamp_within(1_000, 1_600, tolerance));
+        assert!(timestamp_within(1_600, 1_000, tolerance));
+        assert!(!timestamp_within(1_000, 1_601, tolerance));
+        assert!(auth_timestamp() > 1_700_000_000);
+    }
+}
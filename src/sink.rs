@@ -0,0 +1,231 @@
+//! Mirroring of triggered events to external systems
+//!
+//! An [`EventSink`] set with [`ConfigBuilder::event_sink`](crate::ConfigBuilder::event_sink)
+//! receives every event the API accepted, e.g. to forward it to Kafka or an analytics
+//! pipeline without wrapping each call site.
+
+use crate::events::{BatchEvent, Event, EventData};
+use crate::transport::Response;
+use crate::{Config, PusherError, Result};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// Payload passed to the sink for events on encrypted channels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SinkPayload {
+    /// The data as sent to Pusher, i.e. ciphertext on encrypted channels
+    #[default]
+    Encrypted,
+    /// The data before encryption
+    Plaintext,
+}
+
+/// An event accepted by the API
+#[derive(Debug, Clone, PartialEq)]
+pub struct SinkEvent {
+    pub channels: Vec<String>,
+    pub name: String,
+    pub data: String,
+    pub socket_id: Option<String>,
+    pub tags: Option<HashMap<String, String>>,
+    /// Whether `data` is an encrypted payload
+    pub encrypted: bool,
+}
+
+/// Receives every successfully triggered event
+///
+/// Called on the triggering task after the response arrives, so implementations should hand
+/// the event off (e.g. to a channel or producer queue) rather than block.
+pub trait EventSink: Send + Sync {
+    fn send(&self, event: &SinkEvent);
+}
+
+/// Shared handle to the sink stored in the configuration
+#[derive(Clone)]
+pub(crate) struct EventSinkHandle(pub(crate) Arc<dyn EventSink>);
+
+impl fmt::Debug for EventSinkHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "EventSink")
+    }
+}
+
+/// Whether the plaintext of encrypted events must be kept for the sink
+pub(crate) fn wants_plaintext(config: &Config) -> bool {
+    config.event_sink().is_some() && config.event_sink_payload() == SinkPayload::Plaintext
+}
+
+/// Passes an event sent through `/events` to the sink
+///
+/// `plaintext` is the data before encryption when the event's channel is encrypted.
+pub(crate) fn mirror_event(config: &Config, event: &Event, plaintext: Option<&EventData>) {
+    let Some(sink) = config.event_sink() else {
+        return;
+    };
+
+    let (data, encrypted) = match plaintext {
+        Some(plaintext) if config.event_sink_payload() == SinkPayload::Plaintext => {
+            (plaintext.to_string(), false)
+        }
+        Some(_) => (event.data.clone(), true),
+        None => (event.data.clone(), false),
+    };
+    sink.send(&SinkEvent {
+        channels: event.channels.clone(),
+        name: event.name.clone(),
+        data,
+        socket_id: event.socket_id.clone(),
+        tags: event.tags.clone(),
+        encrypted,
+    });
+}
+
+/// Passes the delivered events of a batch to the sink
+///
+/// `plaintext` holds the data of every event before encryption, if [`wants_plaintext`].
+pub(crate) fn mirror_batch(
+    config: &Config,
+    batch: &[BatchEvent],
+    encrypted: &[usize],
+    plaintext: Option<&[String]>,
+    result: &Result<Response>,
+) {
+    let Some(sink) = config.event_sink() else {
+        return;
+    };
+
+    for (index, event) in batch.iter().enumerate() {
+        let delivered = match result {
+            Ok(_) => true,
+            Err(PusherError::Batch { failed_indices, .. }) => !failed_indices.contains(&index),
+            Err(_) => false,
+        };
+        if !delivered {
+            continue;
+        }
+
+        let is_encrypted = encrypted.contains(&index);
+        let (data, encrypted) = match plaintext {
+            Some(plaintext) if is_encrypted => (plaintext[index].clone(), false),
+            _ => (event.data.clone(), is_encrypted),
+        };
+        sink.send(&SinkEvent {
+            channels: vec![event.channel.clone()],
+            name: event.name.clone(),
+            data,
+            socket_id: event.socket_id.clone(),
+            tags: event.tags.clone(),
+            encrypted,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Collect(Mutex<Vec<SinkEvent>>);
+
+    impl EventSink for Collect {
+        fn send(&self, event: &SinkEvent) {
+            self.0.lock().unwrap().push(event.clone());
+        }
+    }
+
+    fn config(sink: Arc<Collect>, payload: SinkPayload) -> Config {
+        Config::builder()
+            .app_id("123")
+            .key("key")
+            .secret("secret")
+            .event_sink_arc(sink)
+            .event_sink_payload(payload)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_mirror_event_payload_choice() {
+        let event = Event {
+            name: "created".to_string(),
+            data: "ciphertext".to_string(),
+            channels: vec!["private-encrypted-orders".to_string()],
+            socket_id: None,
+            info: None,
+            tags: None,
+        };
+        let plaintext = EventData::from_string("plaintext");
+
+        let sink = Arc::new(Collect::default());
+        mirror_event(
+            &config(sink.clone(), SinkPayload::Encrypted),
+            &event,
+            Some(&plaintext),
+        );
+        mirror_event(
+            &config(sink.clone(), SinkPayload::Plaintext),
+            &event,
+            Some(&plaintext),
+        );
+
+        let events = sink.0.lock().unwrap();
+        assert_eq!(
+            (events[0].data.as_str(), events[0].encrypted),
+            ("ciphertext", true)
+        );
+        assert_eq!(
+            (events[1].data.as_str(), events[1].encrypted),
+            ("plaintext", false)
+        );
+    }
+
+    #[cfg(feature = "test-util")]
+    #[tokio::test]
+    async fn test_trigger_mirrors_event() {
+        let sink = Arc::new(Collect::default());
+        let pusher = crate::test_util::MockPusher::with_config(
+            Config::builder()
+                .app_id("123")
+                .key("key")
+                .secret("secret")
+                .event_sink_arc(sink.clone()),
+        )
+        .unwrap();
+
+        let channel = crate::Channel::from_string("orders").unwrap();
+        pusher
+            .trigger([channel], "created", "{}", None)
+            .await
+            .unwrap();
+
+        let events = sink.0.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].channels, vec!["orders"]);
+        assert_eq!(events[0].data, "{}");
+        assert!(!events[0].encrypted);
+    }
+
+    #[test]
+    fn test_mirror_batch_skips_failed_events() {
+        let batch = vec![
+            BatchEvent::new("created", "orders", "a"),
+            BatchEvent::new("created", "private-encrypted-orders", "ciphertext"),
+        ];
+        let plaintext = vec!["a".to_string(), "b".to_string()];
+        let sink = Arc::new(Collect::default());
+        let config = config(sink.clone(), SinkPayload::Plaintext);
+
+        let failed = Err(PusherError::Batch {
+            message: "too large".to_string(),
+            failed_indices: vec![0],
+        });
+        mirror_batch(&config, &batch, &[1], Some(&plaintext), &failed);
+
+        let events = sink.0.lock().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].channels, vec!["private-encrypted-orders"]);
+        assert_eq!(events[0].data, "b");
+    }
+}
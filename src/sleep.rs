@@ -0,0 +1,127 @@
+//! Timer used for retry backoff and request deadlines
+//!
+//! The default `TokioSleeper` (`tokio` feature) requires a tokio runtime. Applications on
+//! async-std or smol can enable the `async-std` or `smol` feature and set the matching sleeper
+//! with `ConfigBuilder::sleeper`; with `tokio` disabled a sleeper must be set. Note that the
+//! default HTTP transport (`reqwest`) enables `tokio` and still needs a tokio reactor for its
+//! sockets, e.g. via the `async-compat` crate, unless a runtime-agnostic `HttpTransport` is
+//! configured with `default-features = false`.
+
+use crate::interceptor::BoxFuture;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Asynchronous timer
+pub trait Sleeper: Send + Sync {
+    /// Completes after `duration` has elapsed
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()>;
+}
+
+/// Sleeper backed by `tokio::time::sleep`
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokioSleeper;
+
+#[cfg(feature = "tokio")]
+impl Sleeper for TokioSleeper {
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
+
+/// Sleeper backed by `async_std::task::sleep`
+#[cfg(feature = "async-std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async-std")))]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AsyncStdSleeper;
+
+#[cfg(feature = "async-std")]
+impl Sleeper for AsyncStdSleeper {
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        Box::pin(async_std::task::sleep(duration))
+    }
+}
+
+/// Sleeper backed by `smol::Timer`
+#[cfg(feature = "smol")]
+#[cfg_attr(docsrs, doc(cfg(feature = "smol")))]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SmolSleeper;
+
+#[cfg(feature = "smol")]
+impl Sleeper for SmolSleeper {
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        Box::pin(async move {
+            smol::Timer::after(duration).await;
+        })
+    }
+}
+
+/// Shared handle to the sleeper stored in the configuration
+#[derive(Clone)]
+pub(crate) struct SleeperHandle(pub(crate) Arc<dyn Sleeper>);
+
+impl SleeperHandle {
+    pub(crate) fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        self.0.sleep(duration)
+    }
+}
+
+impl SleeperHandle {
+    /// The tokio timer, or a configuration error when the `tokio` feature is disabled
+    #[cfg(feature = "tokio")]
+    pub(crate) fn builtin() -> crate::Result<Self> {
+        Ok(Self(Arc::new(TokioSleeper)))
+    }
+
+    #[cfg(not(feature = "tokio"))]
+    pub(crate) fn builtin() -> crate::Result<Self> {
+        Err(crate::PusherError::Config {
+            message: "A sleeper is required when the tokio feature is disabled".to_string(),
+        })
+    }
+}
+
+impl fmt::Debug for SleeperHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Sleeper")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    #[derive(Default)]
+    struct RecordingSleeper(AtomicU64);
+
+    impl Sleeper for RecordingSleeper {
+        fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+            self.0
+                .fetch_add(duration.as_millis() as u64, Ordering::SeqCst);
+            Box::pin(async {})
+        }
+    }
+
+    #[tokio::test]
+    async fn test_custom_sleeper() {
+        let sleeper = Arc::new(RecordingSleeper::default());
+        let handle = SleeperHandle(sleeper.clone());
+        handle.sleep(Duration::from_millis(150)).await;
+        assert_eq!(sleeper.0.load(Ordering::SeqCst), 150);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_tokio_sleeper() {
+        let started = std::time::Instant::now();
+        SleeperHandle::builtin()
+            .unwrap()
+            .sleep(Duration::from_millis(10))
+            .await;
+        assert!(started.elapsed() >= Duration::from_millis(10));
+    }
+}
@@ -0,0 +1,806 @@
+//! Helpers for testing webhook handlers and code that triggers events
+
+use crate::interceptor::{BoxFuture, RequestParts};
+use crate::sleep::{Sleeper, SleeperHandle};
+use crate::transport::{HttpTransport, Response};
+use crate::{Config, Pusher, Result, Token, WebhookEvent, json};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::ops::Deref;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Headers and body of a signed webhook request
+#[derive(Debug, Clone)]
+pub struct SignedWebhook {
+    pub headers: BTreeMap<String, String>,
+    pub body: String,
+}
+
+/// Builds webhook requests signed the same way Pusher signs them
+#[derive(Debug, Clone)]
+pub struct WebhookTestBuilder {
+    token: Token,
+    time_ms: Option<i64>,
+    events: Vec<WebhookEvent>,
+}
+
+#[derive(Serialize)]
+struct WebhookBody<'a> {
+    time_ms: i64,
+    events: &'a [WebhookEvent],
+}
+
+impl WebhookTestBuilder {
+    /// Creates a builder that signs with the given token
+    pub fn new(token: &Token) -> Self {
+        Self {
+            token: token.clone(),
+            time_ms: None,
+            events: Vec::new(),
+        }
+    }
+
+    /// Sets the webhook timestamp (defaults to the current time)
+    pub fn time_ms(mut self, time_ms: i64) -> Self {
+        self.time_ms = Some(time_ms);
+        self
+    }
+
+    /// Adds an event
+    pub fn event(mut self, event: WebhookEvent) -> Self {
+        self.events.push(event);
+        self
+    }
+
+    /// Adds several events
+    pub fn events(mut self, events: impl IntoIterator<Item = WebhookEvent>) -> Self {
+        self.events.extend(events);
+        self
+    }
+
+    /// Serializes the body and signs it
+    pub fn build(&self) -> SignedWebhook {
+        let time_ms = self.time_ms.unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as i64
+        });
+        let body = json::to_string(&WebhookBody {
+            time_ms,
+            events: &self.events,
+        })
+        .expect("webhook events serialize to JSON");
+
+        let mut headers = BTreeMap::new();
+        headers.insert("Content-Type".to_string(), "application/json".to_string());
+        headers.insert("X-Pusher-Key".to_string(), self.token.key.clone());
+        headers.insert("X-Pusher-Signature".to_string(), self.token.sign(&body));
+
+        SignedWebhook { headers, body }
+    }
+
+    /// Builds a signed `POST` request
+    #[cfg(feature = "http")]
+    pub fn build_request(&self) -> http::Request<bytes::Bytes> {
+        let signed = self.build();
+        let mut builder = http::Request::post("/");
+        for (name, value) in &signed.headers {
+            builder = builder.header(name, value);
+        }
+        builder
+            .body(bytes::Bytes::from(signed.body))
+            .expect("webhook headers are valid")
+    }
+}
+
+/// Event captured by [`MockTransport`], one per channel
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedEvent {
+    pub channel: String,
+    pub name: String,
+    pub data: String,
+    pub socket_id: Option<String>,
+}
+
+/// Response returned by [`MockTransport`]
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    status: u16,
+    body: String,
+    latency: Duration,
+}
+
+impl MockResponse {
+    /// Response with the given status and an empty JSON body
+    pub fn status(status: u16) -> Self {
+        Self {
+            status,
+            body: "{}".to_string(),
+            latency: Duration::ZERO,
+        }
+    }
+
+    /// Sets the response body
+    pub fn body(mut self, body: impl Into<String>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    /// Delays the response
+    pub fn latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
+    }
+}
+
+impl Default for MockResponse {
+    fn default() -> Self {
+        Self::status(200)
+    }
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    requests: Vec<RequestParts>,
+    events: Vec<RecordedEvent>,
+    responses: VecDeque<MockResponse>,
+    default_response: MockResponse,
+    sleeper: Option<SleeperHandle>,
+}
+
+#[derive(Deserialize)]
+struct TriggerBody {
+    name: String,
+    data: String,
+    channels: Vec<String>,
+    socket_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct BatchBody {
+    batch: Vec<crate::events::BatchEvent>,
+}
+
+/// In-memory transport that records requests and triggered events
+///
+/// Responses are taken from the queue filled with [`MockTransport::enqueue`], falling back
+/// to the default response (`200 {}` unless changed). Response latency is waited out with
+/// the [`Sleeper`] set by [`MockTransport::set_sleeper`], tokio's timer by default. Clones
+/// share the same state.
+#[derive(Debug, Clone, Default)]
+pub struct MockTransport {
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues a response for the next request
+    pub fn enqueue(&self, response: MockResponse) -> &Self {
+        self.state().responses.push_back(response);
+        self
+    }
+
+    /// Sets the response used when the queue is empty
+    pub fn set_default_response(&self, response: MockResponse) -> &Self {
+        self.state().default_response = response;
+        self
+    }
+
+    /// Sets the timer used to simulate response latency
+    pub fn set_sleeper(&self, sleeper: Arc<dyn Sleeper>) -> &Self {
+        self.state().sleeper = Some(SleeperHandle(sleeper));
+        self
+    }
+
+    /// Every request sent, including retries
+    pub fn requests(&self) -> Vec<RequestParts> {
+        self.state().requests.clone()
+    }
+
+    /// Every event triggered through `/events` or `/batch_events`, one entry per channel
+    pub fn events(&self) -> Vec<RecordedEvent> {
+        self.state().events.clone()
+    }
+
+    /// Events triggered on the given channel
+    pub fn events_on(&self, channel: &str) -> Vec<RecordedEvent> {
+        self.events()
+            .into_iter()
+            .filter(|event| event.channel == channel)
+            .collect()
+    }
+
+    /// Panics unless `event` was triggered on `channel`
+    #[track_caller]
+    pub fn assert_triggered(&self, channel: &str, event: &str) -> RecordedEvent {
+        let events = self.events();
+        match events
+            .iter()
+            .find(|recorded| recorded.channel == channel && recorded.name == event)
+        {
+            Some(recorded) => recorded.clone(),
+            None => panic!(
+                "expected '{}' on '{}', triggered events: {:?}",
+                event, channel, events
+            ),
+        }
+    }
+
+    /// Forgets recorded requests and events
+    pub fn clear(&self) {
+        let mut state = self.state();
+        state.requests.clear();
+        state.events.clear();
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, MockState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn record(&self, request: &RequestParts) -> (MockResponse, Option<SleeperHandle>) {
+        let mut state = self.state();
+        state.requests.push(request.clone());
+
+        let path = url::Url::parse(&request.url)
+            .map(|url| url.path().to_string())
+            .unwrap_or_default();
+        let body = request.body.as_deref().unwrap_or_default();
+        if path.ends_with("/batch_events")
+            && let Ok(batch) = json::from_str::<BatchBody>(body)
+        {
+            state
+                .events
+                .extend(batch.batch.into_iter().map(|event| RecordedEvent {
+                    channel: event.channel,
+                    name: event.name,
+                    data: event.data,
+                    socket_id: event.socket_id,
+                }));
+        } else if path.ends_with("/events")
+            && let Ok(trigger) = json::from_str::<TriggerBody>(body)
+        {
+            for channel in trigger.channels {
+                state.events.push(RecordedEvent {
+                    channel,
+                    name: trigger.name.clone(),
+                    data: trigger.data.clone(),
+                    socket_id: trigger.socket_id.clone(),
+                });
+            }
+        }
+
+        let default = state.default_response.clone();
+        let response = state.responses.pop_front().unwrap_or(default);
+        (response, state.sleeper.clone())
+    }
+}
+
+impl HttpTransport for MockTransport {
+    fn execute<'a>(
+        &'a self,
+        request: &'a RequestParts,
+        _timeout: Duration,
+    ) -> BoxFuture<'a, Result<Response>> {
+        let (response, sleeper) = self.record(request);
+        Box::pin(async move {
+            if !response.latency.is_zero() {
+                let sleeper = match sleeper {
+                    Some(sleeper) => sleeper,
+                    None => SleeperHandle::builtin()?,
+                };
+                sleeper.sleep(response.latency).await;
+            }
+            let status = http::StatusCode::from_u16(response.status).map_err(|e| {
+                crate::PusherError::Validation {
+                    message: format!("Invalid mock status {}: {}", response.status, e),
+                }
+            })?;
+            Ok(Response::new(status, Default::default(), response.body))
+        })
+    }
+}
+
+/// Backoff timer that completes immediately and records every requested delay
+///
+/// Lets tests of retry behavior run without waiting, while still asserting the backoff
+/// schedule. Set it with [`ConfigBuilder::backoff_sleeper`](crate::ConfigBuilder::backoff_sleeper)
+/// so request deadlines keep using real time.
+#[derive(Debug, Clone, Default)]
+pub struct MockSleeper {
+    sleeps: Arc<Mutex<Vec<Duration>>>,
+}
+
+impl MockSleeper {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Delays requested so far, in order
+    pub fn sleeps(&self) -> Vec<Duration> {
+        self.sleeps
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    pub fn clear(&self) {
+        self.sleeps
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+}
+
+impl Sleeper for MockSleeper {
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        self.sleeps
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(duration);
+        Box::pin(async {})
+    }
+}
+
+/// `Pusher` client wired to a [`MockTransport`] and a [`MockSleeper`] for retry backoff
+///
+/// Dereferences to [`Pusher`], so code under test can use it directly.
+#[derive(Debug, Clone)]
+pub struct MockPusher {
+    pusher: Pusher,
+    transport: MockTransport,
+    sleeper: MockSleeper,
+}
+
+impl MockPusher {
+    /// Creates a mock client with test credentials
+    pub fn new() -> Self {
+        Self::with_config(Config::builder().app_id("test").key("key").secret("secret"))
+            .expect("test configuration is valid")
+    }
+
+    /// Creates a mock client from a builder, replacing its transport and backoff timer
+    ///
+    /// Response latency is simulated with the builder's [`sleeper`](crate::ConfigBuilder::sleeper).
+    pub fn with_config(builder: crate::ConfigBuilder) -> Result<Self> {
+        let transport = MockTransport::new();
+        let sleeper = MockSleeper::new();
+        let config = builder
+            .transport(transport.clone())
+            .backoff_sleeper(sleeper.clone())
+            .build()?;
+        transport.set_sleeper(config.sleeper().clone());
+        Ok(Self {
+            pusher: Pusher::new(config)?,
+            transport,
+            sleeper,
+        })
+    }
+
+    pub fn pusher(&self) -> &Pusher {
+        &self.pusher
+    }
+
+    pub fn transport(&self) -> &MockTransport {
+        &self.transport
+    }
+
+    /// Backoff delays slept between retries so far
+    pub fn sleeper(&self) -> &MockSleeper {
+        &self.sleeper
+    }
+}
+
+impl Default for MockPusher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deref for MockPusher {
+    type Target = Pusher;
+
+    fn deref(&self) -> &Pusher {
+        &self.pusher
+    }
+}
+
+/// Wiremock matcher accepting only requests correctly signed for a Pusher app
+///
+/// Checks `auth_signature`, `auth_timestamp`, and `body_md5` with a
+/// [`RequestVerifier`](crate::server::RequestVerifier), and optionally the request path, so
+/// integration tests catch signing regressions instead of matching paths blindly.
+#[cfg(feature = "wiremock")]
+#[cfg_attr(docsrs, doc(cfg(feature = "wiremock")))]
+#[derive(Debug, Clone)]
+pub struct SignedRequest {
+    verifier: crate::server::RequestVerifier,
+    path: Option<String>,
+}
+
+#[cfg(feature = "wiremock")]
+impl SignedRequest {
+    /// Matches requests signed with the given token
+    ///
+    /// # Panics
+    ///
+    /// Panics if the token has no secret or the `md5` feature is disabled; use
+    /// [`SignedRequest::with_verifier`] with a custom digest then.
+    pub fn new(token: &Token) -> Self {
+        Self::with_verifier(
+            crate::server::RequestVerifier::new(token.clone())
+                .expect("SignedRequest needs a token with an app secret and an MD5 provider"),
+        )
+    }
+
+    /// Matches requests accepted by a custom verifier (e.g. with a fixed clock)
+    pub fn with_verifier(verifier: crate::server::RequestVerifier) -> Self {
+        Self {
+            verifier,
+            path: None,
+        }
+    }
+
+    /// Also requires the exact request path, e.g. `/apps/123/events`
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Matches signed `POST /apps/{app_id}/events` requests
+    pub fn events(token: &Token, app_id: &str) -> Self {
+        Self::new(token).path(format!("/apps/{}/events", app_id))
+    }
+
+    /// Matches signed `POST /apps/{app_id}/batch_events` requests
+    pub fn batch_events(token: &Token, app_id: &str) -> Self {
+        Self::new(token).path(format!("/apps/{}/batch_events", app_id))
+    }
+}
+
+#[cfg(feature = "wiremock")]
+impl ::wiremock::Match for SignedRequest {
+    fn matches(&self, request: &::wiremock::Request) -> bool {
+        let path = request.url.path();
+        if self
+            .path
+            .as_deref()
+            .is_some_and(|expected| expected != path)
+        {
+            return false;
+        }
+        self.verifier
+            .verify(
+                request.method.as_str(),
+                path,
+                request.url.query().unwrap_or_default(),
+                Some(&request.body),
+            )
+            .is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Webhook;
+    use crate::json::json;
+
+    #[test]
+    fn test_builder_produces_valid_webhook() {
+        let token = Token::new("test_key", "test_secret");
+        let event = WebhookEvent::ChannelOccupied {
+            channel: "test-channel".to_string(),
+        };
+        let signed = WebhookTestBuilder::new(&token)
+            .time_ms(1234567890)
+            .event(event.clone())
+            .build();
+
+        let webhook = Webhook::new(&token, &signed.headers, &signed.body);
+        assert!(webhook.is_valid(None));
+        assert_eq!(webhook.get_events().unwrap(), vec![event]);
+    }
+
+    #[tokio::test]
+    async fn test_mock_pusher_records_events() {
+        let mock = MockPusher::new();
+        let channels = crate::Channel::parse_list("orders,private-orders").unwrap();
+        mock.trigger(&channels, "created", r#"{"id":1}"#, None)
+            .await
+            .unwrap();
+        mock.trigger_batch(vec![crate::events::BatchEvent::new(
+            "updated", "orders", "{}",
+        )])
+        .await
+        .unwrap();
+
+        let recorded = mock
+            .transport()
+            .assert_triggered("private-orders", "created");
+        assert_eq!(recorded.data, r#"{"id":1}"#);
+        assert_eq!(mock.transport().events_on("orders").len(), 2);
+        assert_eq!(mock.transport().requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_post_retried_only_when_safe() {
+        let mock = MockPusher::new();
+        mock.transport().enqueue(MockResponse::status(503));
+
+        let err = mock.post("/events", &json!({})).await.unwrap_err();
+        assert_eq!(err.status(), Some(503));
+        assert_eq!(mock.transport().requests().len(), 1);
+
+        mock.transport().clear();
+        mock.transport().enqueue(MockResponse::status(503));
+        let options = crate::RequestOptions::default().with_idempotency_key("key-1");
+        mock.post_with_options("/events", &json!({}), &options)
+            .await
+            .unwrap();
+        assert_eq!(mock.transport().requests().len(), 2);
+        assert_eq!(mock.sleeper().sleeps(), vec![Duration::from_millis(100)]);
+
+        mock.transport().clear();
+        mock.transport().enqueue(MockResponse::status(503));
+        let options = crate::RequestOptions::default().with_retry_mode(crate::RetryMode::Always);
+        mock.post_with_options("/events", &json!({}), &options)
+            .await
+            .unwrap();
+        assert_eq!(mock.transport().requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_trigger_retry_mode_param() {
+        let mock = MockPusher::new();
+        let channels = crate::Channel::parse_list("orders").unwrap();
+        mock.transport().enqueue(MockResponse::status(503));
+
+        let params = crate::TriggerParams::builder()
+            .retry_mode(crate::RetryMode::Always)
+            .build();
+        mock.trigger(&channels, "created", "{}", Some(params))
+            .await
+            .unwrap();
+        assert_eq!(mock.transport().requests().len(), 2);
+        let headers = &mock.transport().requests()[0].headers;
+        assert!(
+            headers
+                .iter()
+                .all(|(name, _)| name != crate::pusher::IDEMPOTENCY_KEY_HEADER)
+        );
+    }
+
+    fn batch(len: usize) -> Vec<crate::events::BatchEvent> {
+        (0..len)
+            .map(|i| crate::events::BatchEvent::new("created", format!("orders-{}", i), "{}"))
+            .collect()
+    }
+
+    fn split_pusher(split: bool) -> MockPusher {
+        let config = Config::builder()
+            .app_id("test")
+            .key("key")
+            .secret("secret")
+            .enable_retry(false)
+            .split_oversized_batches(split);
+        MockPusher::with_config(config).unwrap()
+    }
+
+    fn batch_sizes(mock: &MockPusher) -> Vec<usize> {
+        mock.transport()
+            .requests()
+            .iter()
+            .map(|request| {
+                json::from_str::<BatchBody>(request.body.as_deref().unwrap())
+                    .unwrap()
+                    .batch
+                    .len()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_oversized_batch_not_split_by_default() {
+        let mock = split_pusher(false);
+        mock.transport().enqueue(MockResponse::status(413));
+
+        let err = mock.trigger_batch(batch(4)).await.unwrap_err();
+        assert_eq!(err.status(), Some(413));
+        assert_eq!(batch_sizes(&mock), vec![4]);
+    }
+
+    #[tokio::test]
+    async fn test_oversized_batch_split_until_delivered() {
+        let mock = split_pusher(true);
+        mock.transport().enqueue(MockResponse::status(413));
+
+        mock.trigger_batch(batch(4)).await.unwrap();
+        assert_eq!(batch_sizes(&mock), vec![4, 2, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_oversized_batch_split_isolates_event() {
+        let mock = split_pusher(true);
+        // [0..4) too large, [0..2) delivered, [2..4) too large, [2] delivered, [3] too large
+        for status in [413, 200, 413, 200, 413] {
+            mock.transport().enqueue(MockResponse::status(status));
+        }
+
+        let err = mock.trigger_batch(batch(4)).await.unwrap_err();
+        match err {
+            crate::PusherError::Batch { failed_indices, .. } => {
+                assert_eq!(failed_indices, vec![3])
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+        assert_eq!(batch_sizes(&mock), vec![4, 2, 2, 1, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_split_batch_failure_reports_undelivered_events() {
+        let mock = split_pusher(true);
+        mock.transport().enqueue(MockResponse::status(413));
+        mock.transport().enqueue(MockResponse::status(200));
+        mock.transport()
+            .enqueue(MockResponse::status(400).body(r#"{"index":0}"#));
+
+        let err = mock.trigger_batch(batch(4)).await.unwrap_err();
+        assert_eq!(err.rejected_batch_event(), None);
+        match err {
+            crate::PusherError::Batch { failed_indices, .. } => {
+                assert_eq!(failed_indices, vec![2, 3])
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unsplit_batch_rejection_names_event() {
+        let mock = split_pusher(true);
+        mock.transport()
+            .enqueue(MockResponse::status(400).body(r#"{"index":2}"#));
+
+        let err = mock.trigger_batch(batch(4)).await.unwrap_err();
+        let rejected = err.rejected_batch_event().unwrap();
+        assert_eq!((rejected.index, rejected.channel.as_str()), (2, "orders-2"));
+    }
+
+    #[cfg(feature = "encryption")]
+    #[tokio::test]
+    async fn test_encrypted_fan_out_reports_undelivered_channels() {
+        let config = Config::builder()
+            .app_id("test")
+            .key("key")
+            .secret("secret")
+            .enable_retry(false)
+            .encryption_master_key(vec![9u8; 32])
+            .unwrap();
+        let mock = MockPusher::with_config(config).unwrap();
+        mock.transport().enqueue(MockResponse::status(200));
+        mock.transport().enqueue(MockResponse::status(503));
+
+        let channels: Vec<crate::Channel> = (0..12)
+            .map(|i| crate::Channel::from_string(format!("private-encrypted-{}", i)).unwrap())
+            .collect();
+        let params = crate::events::TriggerParams::builder()
+            .idempotency_key("fan-out")
+            .build();
+        let err = mock
+            .trigger(&channels, "created", "{}", Some(params))
+            .await
+            .unwrap_err();
+        match err {
+            crate::PusherError::Batch { failed_indices, .. } => {
+                assert_eq!(failed_indices, vec![10, 11])
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+
+        // Every chunk carries the caller's options, with a distinct idempotency key
+        let keys: Vec<String> = mock
+            .transport()
+            .requests()
+            .iter()
+            .map(|request| {
+                let (_, key) = request
+                    .headers
+                    .iter()
+                    .find(|(name, _)| name == crate::pusher::IDEMPOTENCY_KEY_HEADER)
+                    .unwrap();
+                key.clone()
+            })
+            .collect();
+        assert_eq!(keys, vec!["fan-out-0", "fan-out-1"]);
+    }
+
+    #[tokio::test]
+    async fn test_mock_transport_programmed_responses() {
+        let config = Config::builder()
+            .app_id("test")
+            .key("key")
+            .secret("secret")
+            .enable_retry(false);
+        let mock = MockPusher::with_config(config).unwrap();
+        mock.transport()
+            .enqueue(MockResponse::status(503).body("unavailable"));
+
+        let channel = crate::Channel::from_string("orders").unwrap();
+        let err = mock
+            .trigger([channel.clone()], "created", "{}", None)
+            .await
+            .unwrap_err();
+        match err {
+            crate::PusherError::Request(err) => assert_eq!(err.status, Some(503)),
+            other => panic!("unexpected error: {:?}", other),
+        }
+
+        let response = mock
+            .trigger([channel], "created", "{}", None)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+    }
+
+    #[tokio::test]
+    async fn test_mock_latency_uses_configured_sleeper() {
+        let timer = MockSleeper::new();
+        let config = Config::builder()
+            .app_id("test")
+            .key("key")
+            .secret("secret")
+            .sleeper(timer.clone());
+        let mock = MockPusher::with_config(config).unwrap();
+        mock.transport()
+            .enqueue(MockResponse::status(200).latency(Duration::from_secs(30)));
+
+        let channel = crate::Channel::from_string("orders").unwrap();
+        mock.trigger([channel], "created", "{}", None)
+            .await
+            .unwrap();
+        assert_eq!(timer.sleeps(), vec![Duration::from_secs(30)]);
+    }
+
+    #[cfg(feature = "wiremock")]
+    #[tokio::test]
+    async fn test_signed_request_matcher() {
+        use ::wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let token = Token::new("key", "secret");
+        Mock::given(SignedRequest::events(&token, "123"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("{}"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let pusher_with = |secret: &str| {
+            let config = Config::builder()
+                .app_id("123")
+                .key("key")
+                .secret(secret)
+                .host(server.address().ip().to_string())
+                .port(server.address().port())
+                .use_tls(false)
+                .enable_retry(false)
+                .build()
+                .unwrap();
+            Pusher::new(config).unwrap()
+        };
+        let channel = crate::Channel::from_string("orders").unwrap();
+
+        let wrong = pusher_with("wrong-secret")
+            .trigger([channel.clone()], "created", "{}", None)
+            .await;
+        assert!(wrong.is_err());
+
+        pusher_with("secret")
+            .trigger([channel], "created", "{}", None)
+            .await
+            .unwrap();
+    }
+}
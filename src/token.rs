@@ -1,4 +1,3 @@
-use crate::util;
 use hmac::{Hmac, Mac};
 use sha2::Sha256;
 use std::fmt;
@@ -7,10 +6,14 @@ use zeroize::Zeroize;
 type HmacSha256 = Hmac<Sha256>;
 
 /// Token for signing and verifying data against the app key and secret
+///
+/// Signing always uses the current secret; verification also accepts any retired secrets,
+/// so webhooks and requests signed before a secret rotation keep validating.
 #[derive(Clone)]
 pub struct Token {
     pub key: String,
     secret: SecretString,
+    retired: Vec<SecretString>,
 }
 
 /// Wrapper for secret that ensures it's zeroed on drop
@@ -23,29 +26,81 @@ impl Token {
         Self {
             key: key.into(),
             secret: SecretString(secret.into()),
+            retired: Vec::new(),
         }
     }
 
-    /// Signs the string using HMAC-SHA256
-    pub fn sign(&self, data: &str) -> String {
-        let mut mac = HmacSha256::new_from_slice(self.secret.0.as_bytes())
-            .expect("HMAC can take key of any size");
-        mac.update(data.as_bytes());
+    /// Adds a retired secret that is still accepted for verification but never used to sign
+    ///
+    /// An empty secret is ignored: anyone could sign with it.
+    pub fn with_retired_secret(mut self, secret: impl Into<String>) -> Self {
+        let secret = SecretString(secret.into());
+        if !secret.0.is_empty() {
+            self.retired.push(secret);
+        }
+        self
+    }
 
-        // Use hex formatting directly for better performance
-        format!("{:x}", mac.finalize().into_bytes())
+    /// Signs the string using HMAC-SHA256, returning the lowercase hex digest
+    pub fn sign(&self, data: &str) -> String {
+        hex::encode(self.sign_bytes(data.as_bytes()))
     }
 
-    /// Verifies the signature against the data
+    /// Verifies a hex signature against the data
     pub fn verify(&self, data: &str, signature: &str) -> bool {
-        let expected = self.sign(data);
-        util::secure_compare(&expected, signature)
+        hex::decode(signature).is_ok_and(|signature| self.verify_bytes(data.as_bytes(), &signature))
+    }
+
+    /// Signs raw bytes using HMAC-SHA256, returning the raw digest
+    pub fn sign_bytes(&self, data: &[u8]) -> [u8; 32] {
+        mac(&self.secret, data).finalize().into_bytes().into()
+    }
+
+    /// Verifies a raw digest against raw bytes in constant time
+    ///
+    /// The current and all retired secrets are always checked, so timing does not reveal which
+    /// one matched.
+    pub fn verify_bytes(&self, data: &[u8], signature: &[u8]) -> bool {
+        std::iter::once(&self.secret)
+            .chain(&self.retired)
+            .fold(false, |valid, secret| {
+                valid | mac(secret, data).verify_slice(signature).is_ok()
+            })
+    }
+
+    /// Checks whether the token holds a secret (it may not when signing is delegated)
+    pub(crate) fn has_secret(&self) -> bool {
+        !self.secret.0.is_empty()
+    }
+
+    /// Fails if the token has no secret to sign or verify with locally
+    ///
+    /// An empty secret is a publicly known HMAC key, so it must never be used.
+    pub(crate) fn require_secret(&self) -> crate::Result<()> {
+        if self.has_secret() {
+            return Ok(());
+        }
+        Err(crate::PusherError::Config {
+            message: format!("Token for key '{}' has no app secret", self.key),
+        })
     }
 
     /// Gets the secret as a string (for internal use only)
     pub(crate) fn secret_string(&self) -> String {
         self.secret.0.clone()
     }
+
+    /// Gets the retired secrets (for internal use only)
+    pub(crate) fn retired_secrets(&self) -> impl Iterator<Item = &str> {
+        self.retired.iter().map(|secret| secret.0.as_str())
+    }
+}
+
+fn mac(secret: &SecretString, data: &[u8]) -> HmacSha256 {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.0.as_bytes()).expect("HMAC can take key of any size");
+    mac.update(data);
+    mac
 }
 
 impl fmt::Debug for Token {
@@ -53,6 +108,7 @@ impl fmt::Debug for Token {
         f.debug_struct("Token")
             .field("key", &self.key)
             .field("secret", &"[REDACTED]")
+            .field("retired_secrets", &self.retired.len())
             .finish()
     }
 }
@@ -72,6 +128,37 @@ mod tests {
         assert!(!token.verify(data, "wrong_signature"));
     }
 
+    #[test]
+    fn test_sign_and_verify_bytes() {
+        let token = Token::new("test_key", "test_secret");
+        let data = b"\x00binary\xff";
+        let signature = token.sign_bytes(data);
+
+        assert!(token.verify_bytes(data, &signature));
+        assert!(!token.verify_bytes(b"other", &signature));
+        assert!(!token.verify_bytes(data, &signature[..16]));
+        assert_eq!(
+            hex::encode(token.sign_bytes(b"test_data")),
+            token.sign("test_data")
+        );
+    }
+
+    #[test]
+    fn test_retired_secrets_verify_only() {
+        let old = Token::new("key", "old_secret");
+        let token = Token::new("key", "new_secret").with_retired_secret("old_secret");
+        let data = "test_data";
+
+        assert!(token.verify(data, &old.sign(data)));
+        assert!(token.verify(data, &token.sign(data)));
+        assert_eq!(token.sign(data), Token::new("key", "new_secret").sign(data));
+        assert!(!token.verify(data, &Token::new("key", "other").sign(data)));
+        assert!(!old.verify(data, &token.sign(data)));
+
+        let ignored = Token::new("key", "new_secret").with_retired_secret("");
+        assert!(!ignored.verify(data, &Token::new("key", "").sign(data)));
+    }
+
     #[test]
     fn test_hmac_consistency() {
         let token = Token::new("key", "secret");
@@ -0,0 +1,130 @@
+//! W3C trace-context propagation for outgoing requests
+
+use crate::{PusherError, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::{Arc, LazyLock};
+
+/// Header carrying the W3C trace parent
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+
+/// Header carrying vendor-specific W3C trace state
+pub const TRACESTATE_HEADER: &str = "tracestate";
+
+/// Header carrying the client-generated request ID, echoed back by compatible servers
+pub const REQUEST_ID_HEADER: &str = "X-Request-Id";
+
+static TRACEPARENT_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[0-9a-f]{2}-[0-9a-f]{32}-[0-9a-f]{16}-[0-9a-f]{2}$").unwrap());
+
+/// W3C trace context attached to an outgoing request
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceContext {
+    pub traceparent: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tracestate: Option<String>,
+}
+
+impl TraceContext {
+    /// Creates a trace context from a `traceparent` header value
+    pub fn new(traceparent: impl Into<String>) -> Result<Self> {
+        let traceparent = traceparent.into();
+        if !TRACEPARENT_PATTERN.is_match(&traceparent) {
+            return Err(PusherError::Validation {
+                message: format!("Invalid traceparent: '{}'", traceparent),
+            });
+        }
+        Ok(Self {
+            traceparent,
+            tracestate: None,
+        })
+    }
+
+    /// Sets the `tracestate` header value
+    pub fn with_tracestate(mut self, tracestate: impl Into<String>) -> Self {
+        self.tracestate = Some(tracestate.into());
+        self
+    }
+
+    /// Gets the headers to inject into a request
+    pub(crate) fn headers(&self) -> Vec<(&'static str, String)> {
+        let mut headers = vec![(TRACEPARENT_HEADER, self.traceparent.clone())];
+        if let Some(ref tracestate) = self.tracestate {
+            headers.push((TRACESTATE_HEADER, tracestate.clone()));
+        }
+        headers
+    }
+}
+
+/// Source of the trace context for outgoing requests
+pub trait TracePropagator: Send + Sync {
+    /// Gets the trace context of the current span, if any
+    fn current(&self) -> Option<TraceContext>;
+
+    /// Records the request ID returned by the server against the current span
+    fn record_request_id(&self, _request_id: &str) {}
+}
+
+/// Shared handle to a trace propagator stored in the configuration
+#[derive(Clone)]
+pub(crate) struct PropagatorHandle(pub(crate) Arc<dyn TracePropagator>);
+
+impl fmt::Debug for PropagatorHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "TracePropagator")
+    }
+}
+
+/// Propagator that reads the current OpenTelemetry context through the global propagator
+#[cfg(feature = "opentelemetry")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OpenTelemetryPropagator;
+
+#[cfg(feature = "opentelemetry")]
+impl TracePropagator for OpenTelemetryPropagator {
+    fn current(&self) -> Option<TraceContext> {
+        let mut carrier = std::collections::HashMap::new();
+        opentelemetry::global::get_text_map_propagator(|propagator| {
+            propagator.inject_context(&opentelemetry::Context::current(), &mut carrier)
+        });
+
+        let traceparent = carrier.remove(TRACEPARENT_HEADER)?;
+        Some(TraceContext {
+            traceparent,
+            tracestate: carrier.remove(TRACESTATE_HEADER).filter(|s| !s.is_empty()),
+        })
+    }
+
+    fn record_request_id(&self, request_id: &str) {
+        opentelemetry::trace::get_active_span(|span| {
+            span.set_attribute(opentelemetry::KeyValue::new(
+                "pusher.request_id",
+                request_id.to_string(),
+            ))
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_traceparent_validation() {
+        let traceparent = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+        let context = TraceContext::new(traceparent).unwrap();
+        assert_eq!(
+            context.headers(),
+            vec![(TRACEPARENT_HEADER, traceparent.to_string())]
+        );
+
+        let context = context.with_tracestate("vendor=value");
+        assert_eq!(context.headers().len(), 2);
+
+        assert!(TraceContext::new("not-a-traceparent").is_err());
+        assert!(
+            TraceContext::new("00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01").is_err()
+        );
+    }
+}
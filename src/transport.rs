@@ -0,0 +1,316 @@
+//! Pluggable HTTP transport
+//!
+//! `Pusher` signs, intercepts and retries requests; the transport only sends the final
+//! request and returns the fully read [`Response`]. The default [`ReqwestTransport`]
+//! (`reqwest` feature) uses a pooled `reqwest::Client`. Without that feature, set a
+//! transport with `ConfigBuilder::transport` (hyper, unix sockets, proxies, test doubles).
+
+use crate::Result;
+use crate::interceptor::{BoxFuture, RequestParts};
+use crate::json;
+use crate::ratelimit::RateLimit;
+use http::{Extensions, HeaderMap, StatusCode};
+use serde::de::DeserializeOwned;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Sends a single, fully signed HTTP attempt
+pub trait HttpTransport: Send + Sync {
+    /// Sends the request and reads the whole response
+    ///
+    /// Non-2xx responses must be returned as `Ok`; errors are reserved for requests that
+    /// received no response, which `Pusher` may retry. Report them as
+    /// [`PusherError::Http`] with an [`HttpError`](crate::HttpError), marked with
+    /// `connect()` when nothing was sent.
+    fn execute<'a>(
+        &'a self,
+        request: &'a RequestParts,
+        timeout: Duration,
+    ) -> BoxFuture<'a, Result<Response>>;
+}
+
+/// Fully read HTTP response
+#[derive(Debug, Clone, Default)]
+pub struct Response {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Vec<u8>,
+    extensions: Extensions,
+}
+
+impl Response {
+    /// Creates a response from its parts, e.g. in a custom transport
+    pub fn new(status: StatusCode, headers: HeaderMap, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            headers,
+            body: body.into(),
+            extensions: Extensions::new(),
+        }
+    }
+
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// Values attached to the response by the client, such as its
+    /// [`IdempotencyKey`](crate::IdempotencyKey)
+    pub fn extensions(&self) -> &Extensions {
+        &self.extensions
+    }
+
+    pub fn extensions_mut(&mut self) -> &mut Extensions {
+        &mut self.extensions
+    }
+
+    /// Rate-limit headers of the response, if any were sent
+    pub fn rate_limit(&self) -> Option<RateLimit> {
+        RateLimit::from_headers(&self.headers)
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.body
+    }
+
+    /// Body as text, replacing invalid UTF-8
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+
+    /// Deserializes the JSON body
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T> {
+        Ok(json::from_slice(&self.body)?)
+    }
+}
+
+/// Default transport backed by `reqwest`
+#[cfg(feature = "reqwest")]
+#[cfg_attr(docsrs, doc(cfg(feature = "reqwest")))]
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
+
+#[cfg(feature = "reqwest")]
+impl ReqwestTransport {
+    /// Creates a transport that sends requests through `client`
+    pub fn new(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+}
+
+#[cfg(feature = "reqwest")]
+impl HttpTransport for ReqwestTransport {
+    fn execute<'a>(
+        &'a self,
+        request: &'a RequestParts,
+        timeout: Duration,
+    ) -> BoxFuture<'a, Result<Response>> {
+        Box::pin(async move {
+            let method = reqwest::Method::from_bytes(request.method.as_bytes()).map_err(|_| {
+                crate::PusherError::Validation {
+                    message: format!("Invalid HTTP method: {}", request.method),
+                }
+            })?;
+
+            let mut builder = self.client.request(method, &request.url).timeout(timeout);
+            for (name, value) in &request.headers {
+                builder = builder.header(name.as_str(), value.as_str());
+            }
+            if let Some(ref body) = request.body {
+                builder = builder.body(body.clone());
+            }
+
+            let response = builder.send().await?;
+            let status = response.status();
+            let headers = response.headers().clone();
+            let body = response.bytes().await?;
+            Ok(Response::new(status, headers, body))
+        })
+    }
+}
+
+/// Placeholder used without the `reqwest` feature when no transport is configured
+#[cfg(not(feature = "reqwest"))]
+pub(crate) struct MissingTransport;
+
+#[cfg(not(feature = "reqwest"))]
+impl HttpTransport for MissingTransport {
+    fn execute<'a>(
+        &'a self,
+        _request: &'a RequestParts,
+        _timeout: Duration,
+    ) -> BoxFuture<'a, Result<Response>> {
+        Box::pin(async {
+            Err(crate::PusherError::Config {
+                message: "No HTTP transport configured; enable the `reqwest` feature or set one \
+                          with ConfigBuilder::transport"
+                    .to_string(),
+            })
+        })
+    }
+}
+
+/// Records requests instead of sending them, answering each with an empty `200`
+#[derive(Default)]
+pub(crate) struct CaptureTransport {
+    requests: std::sync::Mutex<Vec<RequestParts>>,
+}
+
+impl CaptureTransport {
+    pub(crate) fn take(&self) -> Vec<RequestParts> {
+        std::mem::take(&mut *self.requests.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+}
+
+impl HttpTransport for CaptureTransport {
+    fn execute<'a>(
+        &'a self,
+        request: &'a RequestParts,
+        _timeout: Duration,
+    ) -> BoxFuture<'a, Result<Response>> {
+        self.requests
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(request.clone());
+        Box::pin(async { Ok(Response::new(StatusCode::OK, HeaderMap::new(), "{}")) })
+    }
+}
+
+/// Shared handle to the transport stored in the configuration
+#[derive(Clone)]
+pub(crate) struct TransportHandle(pub(crate) Arc<dyn HttpTransport>);
+
+impl fmt::Debug for TransportHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "HttpTransport")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json::JsonValueTrait;
+    use crate::{Channel, Config, HttpError, Pusher, PusherError};
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct RecordingTransport {
+        requests: Mutex<Vec<RequestParts>>,
+    }
+
+    impl HttpTransport for RecordingTransport {
+        fn execute<'a>(
+            &'a self,
+            request: &'a RequestParts,
+            _timeout: Duration,
+        ) -> BoxFuture<'a, Result<Response>> {
+            self.requests.lock().unwrap().push(request.clone());
+            Box::pin(async {
+                let mut headers = HeaderMap::new();
+                headers.insert("x-ratelimit-remaining", "9".parse().unwrap());
+                Ok(Response::new(StatusCode::OK, headers, r#"{"channels":{}}"#))
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_custom_transport() {
+        let transport = Arc::new(RecordingTransport::default());
+        let config = Config::builder()
+            .app_id("123")
+            .key("key")
+            .secret("secret")
+            .transport_arc(transport.clone())
+            .build()
+            .unwrap();
+        let pusher = Pusher::new(config).unwrap();
+
+        let channel = Channel::from_string("orders").unwrap();
+        let response = pusher
+            .trigger([channel], "created", "{}", None)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.rate_limit().unwrap().remaining, Some(9));
+        let body: json::Value = response.json().unwrap();
+        assert!(body.get("channels").is_some());
+
+        let requests = transport.requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, "POST");
+        assert!(requests[0].url.contains("/apps/123/events?"));
+        assert!(
+            requests[0]
+                .headers
+                .iter()
+                .any(|(name, value)| name == "Content-Type" && value == "application/json")
+        );
+    }
+
+    struct RefusingTransport;
+
+    impl HttpTransport for RefusingTransport {
+        fn execute<'a>(
+            &'a self,
+            _request: &'a RequestParts,
+            _timeout: Duration,
+        ) -> BoxFuture<'a, Result<Response>> {
+            Box::pin(async { Err(HttpError::new("connection refused").connect().into()) })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_custom_transport_connect_error_is_retried() {
+        let config = Config::builder()
+            .app_id("123")
+            .key("key")
+            .secret("secret")
+            .transport(RefusingTransport)
+            .max_retries(1)
+            .build()
+            .unwrap();
+        let pusher = Pusher::new(config).unwrap();
+
+        let channel = Channel::from_string("orders").unwrap();
+        let error = pusher
+            .trigger([channel], "created", "{}", None)
+            .await
+            .unwrap_err();
+        let PusherError::Request(error) = error else {
+            panic!("expected request error, got {:?}", error);
+        };
+        assert_eq!(error.attempts.len(), 2);
+        assert_eq!(
+            error.attempts[0].error.as_deref(),
+            Some("connection refused")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_single_attempt_failure_is_request_error() {
+        let config = Config::builder()
+            .app_id("123")
+            .key("key")
+            .secret("secret")
+            .transport(RefusingTransport)
+            .enable_retry(false)
+            .build()
+            .unwrap();
+        let pusher = Pusher::new(config).unwrap();
+
+        let error = pusher.get("/channels", None).await.unwrap_err();
+        assert!(error.is_retryable());
+        let PusherError::Request(error) = error else {
+            panic!("expected request error, got {:?}", error);
+        };
+        assert_eq!(error.attempts.len(), 1);
+        assert_eq!(error.message, "connection refused");
+        assert!(error.source.as_ref().unwrap().is_connect());
+    }
+}
@@ -21,8 +21,71 @@ pub fn to_ordered_array(map: &BTreeMap<String, String>) -> Vec<String> {
         .collect()
 }
 
+/// Percent-encodes a query string key or value, leaving only RFC 3986 unreserved characters
+pub fn encode_query_component(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => write!(&mut encoded, "%{:02X}", byte).unwrap(),
+        }
+    }
+    encoded
+}
+
+/// Decodes `%XX` escapes, leaving malformed ones as they are
+pub fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        // `from_str_radix` alone would accept a sign, decoding e.g. `%+A`
+        let escaped = (bytes[i] == b'%')
+            .then(|| bytes.get(i + 1..i + 3))
+            .flatten()
+            .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Converts a map to a percent-encoded query string in key order
+pub fn to_encoded_query_string(map: &BTreeMap<String, String>) -> String {
+    map.iter()
+        .map(|(key, value)| {
+            format!(
+                "{}={}",
+                encode_query_component(key),
+                encode_query_component(value)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// Decodes a query string into a map, reversing [`to_encoded_query_string`]
+pub fn parse_query_string(query: &str) -> BTreeMap<String, String> {
+    url::form_urlencoded::parse(query.as_bytes())
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect()
+}
+
 /// Calculates MD5 hash of the input
 /// Note: MD5 is used here for compatibility with Pusher's protocol, not for security
+#[cfg(feature = "md5")]
 pub fn get_md5(body: &str) -> String {
     let digest = md5::compute(body.as_bytes());
     hex::encode(digest.as_ref())
@@ -41,6 +104,28 @@ pub fn secure_compare(a: &str, b: &str) -> bool {
     a_bytes.ct_eq(b_bytes).into()
 }
 
+/// Query parameters of a signed request that must not appear in logs
+pub const SIGNED_URL_SECRET_PARAMS: &[&str] = &["auth_key", "auth_signature"];
+
+/// Replaces the auth key and signature in a signed URL with `[REDACTED]`
+pub fn redact_signed_url(url: &str) -> String {
+    let Some((base, query)) = url.split_once('?') else {
+        return url.to_string();
+    };
+
+    let query: Vec<String> = query
+        .split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((key, _)) if SIGNED_URL_SECRET_PARAMS.contains(&key) => {
+                format!("{}=[REDACTED]", key)
+            }
+            _ => pair.to_string(),
+        })
+        .collect();
+
+    format!("{}?{}", base, query.join("&"))
+}
+
 /// Checks if a channel is encrypted (moved from channel.rs for backward compatibility)
 pub fn is_encrypted_channel(channel: &str) -> bool {
     channel.starts_with("private-encrypted-")
@@ -139,6 +224,35 @@ pub fn format_duration(duration: std::time::Duration) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_encoded_query_string_round_trip() {
+        let mut map = BTreeMap::new();
+        map.insert(
+            "filter_by_prefix".to_string(),
+            "presence-room#1".to_string(),
+        );
+        map.insert("info".to_string(), "a&b=c d+e%f/ü".to_string());
+
+        let query = to_encoded_query_string(&map);
+        assert_eq!(
+            query,
+            "filter_by_prefix=presence-room%231&info=a%26b%3Dc%20d%2Be%25f%2F%C3%BC"
+        );
+        assert_eq!(parse_query_string(&query), map);
+    }
+
+    #[test]
+    fn test_percent_decode() {
+        assert_eq!(percent_decode("a%2Fb%3Ac%C3%BC"), "a/b:cü");
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("%zz%4"), "%zz%4");
+        assert_eq!(percent_decode("%+A%-1"), "%+A%-1");
+        assert_eq!(
+            percent_decode(&encode_query_component("k3y:s/cr+t")),
+            "k3y:s/cr+t"
+        );
+    }
+
     #[test]
     fn test_secure_compare() {
         assert!(secure_compare("hello", "hello"));
@@ -199,6 +313,17 @@ mod tests {
         assert_eq!(result, vec!["key1=value1", "key2=value2"]);
     }
 
+    #[test]
+    fn test_redact_signed_url() {
+        let url = "https://api.pusherapp.com/apps/1/events?auth_key=key&auth_timestamp=1&auth_signature=abc";
+        assert_eq!(
+            redact_signed_url(url),
+            "https://api.pusherapp.com/apps/1/events?auth_key=[REDACTED]&auth_timestamp=1&auth_signature=[REDACTED]"
+        );
+        assert_eq!(redact_signed_url("/apps/1/events"), "/apps/1/events");
+    }
+
+    #[cfg(feature = "md5")]
     #[test]
     fn test_get_md5() {
         let hash = get_md5("hello");
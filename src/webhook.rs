@@ -1,16 +1,28 @@
-use crate::{PusherError, Result, Token, WebhookError};
-use serde::{Deserialize, Serialize};
+use crate::channel::ChannelPattern;
+use crate::clock::{Clock, ClockHandle};
+use crate::interceptor::BoxFuture;
+use crate::{
+    Pusher, PusherError, Result, Token, WebhookError, WebhookValidationError, events, json, util,
+};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize};
 use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::future::Future;
+use std::ops::Deref;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 /// Webhook for validating and accessing Pusher webhook data
 #[derive(Debug)]
 pub struct Webhook {
-    token: Token,
+    tokens: Vec<Token>,
     key: Option<String>,
     signature: Option<String>,
     content_type: Option<String>,
-    body: String,
+    body: Vec<u8>,
     data: Option<WebhookData>,
+    clock: ClockHandle,
 }
 
 /// Webhook data structure matching Pusher's format
@@ -19,11 +31,66 @@ pub struct WebhookData {
     /// The timestamp of the webhook in milliseconds
     pub time_ms: i64,
     /// The events received with the webhook
+    ///
+    /// Numeric and boolean fields (such as `subscription_count`) are kept as strings.
+    #[serde(deserialize_with = "deserialize_events")]
     pub events: Vec<HashMap<String, String>>,
 }
 
-/// Strongly typed webhook event
+/// A `client_event` whose `data` has been deserialized into `T`
 #[derive(Debug, Clone, PartialEq)]
+pub struct ClientEvent<T> {
+    pub channel: String,
+    pub event: String,
+    pub data: T,
+    pub socket_id: String,
+    pub user_id: Option<String>,
+}
+
+/// Scalar field value of a raw webhook event
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawField {
+    Str(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Null(()),
+}
+
+/// Deserializes raw events, converting scalar fields to strings and dropping nulls
+fn deserialize_events<'de, D>(
+    deserializer: D,
+) -> std::result::Result<Vec<HashMap<String, String>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let events = Vec::<HashMap<String, RawField>>::deserialize(deserializer)?;
+    Ok(events
+        .into_iter()
+        .map(|event| {
+            event
+                .into_iter()
+                .filter_map(|(key, value)| {
+                    let value = match value {
+                        RawField::Str(s) => s,
+                        RawField::Int(i) => i.to_string(),
+                        RawField::Float(f) => f.to_string(),
+                        RawField::Bool(b) => b.to_string(),
+                        RawField::Null(()) => return None,
+                    };
+                    Some((key, value))
+                })
+                .collect()
+        })
+        .collect())
+}
+
+/// Strongly typed webhook event
+///
+/// Serializes to the same JSON shape Pusher sends, tagged by `name`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "name", rename_all = "snake_case")]
 pub enum WebhookEvent {
     ChannelOccupied {
         channel: String,
@@ -44,18 +111,36 @@ pub enum WebhookEvent {
         event: String,
         data: String,
         socket_id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         user_id: Option<String>,
     },
     CacheMiss {
         channel: String,
         event: String,
     },
+    SubscriptionCount {
+        channel: String,
+        subscription_count: u64,
+    },
+    #[serde(untagged)]
     Unknown(HashMap<String, String>),
 }
 
 impl Webhook {
     /// Creates a new webhook from request data
     pub fn new(token: &Token, headers: &BTreeMap<String, String>, body: &str) -> Self {
+        Self::from_bytes(token, headers, body.as_bytes())
+    }
+
+    /// Creates a new webhook from request headers and the exact raw body bytes
+    pub fn from_bytes(token: &Token, headers: &BTreeMap<String, String>, body: &[u8]) -> Self {
+        Self::with_tokens(std::slice::from_ref(token), headers, body)
+    }
+
+    /// Creates a new webhook accepting signatures from any of the given tokens
+    ///
+    /// Useful during key rotation, when webhooks may be signed with either the old or new secret.
+    pub fn with_tokens(tokens: &[Token], headers: &BTreeMap<String, String>, body: &[u8]) -> Self {
         // Normalize header names to lowercase for case-insensitive lookup
         let normalized_headers: BTreeMap<String, String> = headers
             .iter()
@@ -66,45 +151,152 @@ impl Webhook {
         let signature = normalized_headers.get("x-pusher-signature").cloned();
         let content_type = normalized_headers.get("content-type").cloned();
 
+        Self::from_parts(tokens, key, signature, content_type, body)
+    }
+
+    /// Creates a webhook from an HTTP header map and the raw body
+    ///
+    /// When a header is repeated, the first value is used.
+    #[cfg(feature = "http")]
+    pub fn from_http_parts(token: &Token, headers: &http::HeaderMap, body: &[u8]) -> Self {
+        Self::from_http_parts_with_tokens(std::slice::from_ref(token), headers, body)
+    }
+
+    /// Creates a webhook from an HTTP header map, accepting signatures from any of the given tokens
+    #[cfg(feature = "http")]
+    pub fn from_http_parts_with_tokens(
+        tokens: &[Token],
+        headers: &http::HeaderMap,
+        body: &[u8],
+    ) -> Self {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+
+        Self::from_parts(
+            tokens,
+            header("x-pusher-key"),
+            header("x-pusher-signature"),
+            header("content-type"),
+            body,
+        )
+    }
+
+    /// Creates a webhook from an HTTP request
+    #[cfg(feature = "http")]
+    pub fn from_http_request(token: &Token, request: &http::Request<bytes::Bytes>) -> Self {
+        Self::from_http_parts(token, request.headers(), request.body())
+    }
+
+    /// Builds the webhook from the extracted header values
+    fn from_parts(
+        tokens: &[Token],
+        key: Option<String>,
+        signature: Option<String>,
+        content_type: Option<String>,
+        body: &[u8],
+    ) -> Self {
         let data = if Self::validate_content_type(&content_type) {
-            sonic_rs::from_str::<WebhookData>(body).ok()
+            json::from_slice::<WebhookData>(body).ok()
         } else {
             None
         };
 
         Self {
-            token: token.clone(),
+            tokens: tokens.to_vec(),
             key,
             signature,
             content_type,
-            body: body.to_string(),
+            body: body.to_vec(),
             data,
+            clock: ClockHandle::default(),
         }
     }
 
+    /// Sets the clock used by `validate_with` freshness checks (defaults to the system clock)
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = ClockHandle(clock);
+        self
+    }
+
     /// Validates the webhook signature and content
     pub fn is_valid(&self, extra_tokens: Option<&[Token]>) -> bool {
-        if !self.is_body_valid() {
-            return false;
+        self.validate(extra_tokens).is_ok()
+    }
+
+    /// Validates the webhook, reporting why validation failed
+    ///
+    /// Checks run in this order: content type, body, presence of the key and signature
+    /// headers, then key and signature against every candidate token. The key and signature
+    /// comparisons are constant-time and every token is checked, so timing does not reveal
+    /// which token (if any) matched.
+    pub fn validate(
+        &self,
+        extra_tokens: Option<&[Token]>,
+    ) -> std::result::Result<ValidatedWebhook<'_>, WebhookValidationError> {
+        if !self.is_content_type_valid() {
+            return Err(WebhookValidationError::InvalidContentType(
+                self.content_type.clone(),
+            ));
         }
+        let data = self
+            .data
+            .as_ref()
+            .ok_or(WebhookValidationError::InvalidBody)?;
+        let key = self
+            .key
+            .as_deref()
+            .ok_or(WebhookValidationError::MissingKey)?;
+        let signature = self
+            .signature
+            .as_deref()
+            .ok_or(WebhookValidationError::MissingSignature)?;
 
-        let tokens_to_check = if let Some(extra) = extra_tokens {
-            let mut tokens = vec![&self.token];
-            tokens.extend(extra.iter());
-            tokens
-        } else {
-            vec![&self.token]
-        };
+        // Decode once and compare raw digests; malformed hex matches no token
+        let signature = hex::decode(signature).ok();
 
-        for token in tokens_to_check {
-            if let (Some(key), Some(signature)) = (&self.key, &self.signature) {
-                if key == &token.key && token.verify(&self.body, signature) {
-                    return true;
-                }
-            }
+        let mut key_known = false;
+        let mut signature_valid = false;
+        for token in self.tokens.iter().chain(extra_tokens.unwrap_or_default()) {
+            let key_matches = util::secure_compare(&token.key, key);
+            // A token without a secret would accept HMACs keyed with the empty string
+            let signature_matches = token.has_secret()
+                && signature
+                    .as_deref()
+                    .is_some_and(|signature| token.verify_bytes(&self.body, signature));
+            key_known |= key_matches;
+            signature_valid |= key_matches & signature_matches;
         }
 
-        false
+        if !key_known {
+            return Err(WebhookValidationError::UnknownKey(key.to_string()));
+        }
+        if !signature_valid {
+            return Err(WebhookValidationError::SignatureMismatch);
+        }
+
+        Ok(ValidatedWebhook {
+            webhook: self,
+            data,
+        })
+    }
+
+    /// Validates the webhook and rejects it if `time_ms` is outside the tolerance window
+    ///
+    /// This protects receivers from replayed requests carrying an old, validly signed body.
+    pub fn validate_with(
+        &self,
+        tolerance: Tolerance,
+    ) -> std::result::Result<ValidatedWebhook<'_>, WebhookValidationError> {
+        let validated = self.validate(None)?;
+        let time_ms = validated.data().time_ms;
+        if !tolerance.contains(time_ms, self.clock.0.now()) {
+            return Err(WebhookValidationError::OutsideTolerance { time_ms });
+        }
+        Ok(validated)
     }
 
     /// Checks if the content type is valid (application/json)
@@ -131,7 +323,7 @@ impl Webhook {
             PusherError::Webhook(WebhookError::new(
                 "Invalid webhook body",
                 self.content_type.clone(),
-                &self.body,
+                String::from_utf8_lossy(&self.body),
                 self.signature.clone(),
             ))
         })
@@ -148,6 +340,88 @@ impl Webhook {
         Ok(raw_events.iter().map(|e| parse_webhook_event(e)).collect())
     }
 
+    /// Gets the events, decrypting `client_event` data on encrypted channels
+    pub fn get_events_decrypted(&self, pusher: &Pusher) -> Result<Vec<WebhookEvent>> {
+        self.get_events()?
+            .into_iter()
+            .map(|event| match event {
+                WebhookEvent::ClientEvent {
+                    channel,
+                    event,
+                    data,
+                    socket_id,
+                    user_id,
+                } if util::is_encrypted_channel(&channel) => Ok(WebhookEvent::ClientEvent {
+                    data: events::decrypt(pusher, &channel, &data)?,
+                    channel,
+                    event,
+                    socket_id,
+                    user_id,
+                }),
+                other => Ok(other),
+            })
+            .collect()
+    }
+
+    /// Gets the client events, deserializing each `data` field into `T`
+    ///
+    /// Other event types are skipped. Fails on the first client event whose data does not match `T`.
+    pub fn get_events_typed<T: DeserializeOwned>(&self) -> Result<Vec<ClientEvent<T>>> {
+        let mut typed = Vec::new();
+        for event in self.get_events()? {
+            if !matches!(event, WebhookEvent::ClientEvent { .. }) {
+                continue;
+            }
+            let data = event.data_as::<T>()?;
+            if let WebhookEvent::ClientEvent {
+                channel,
+                event,
+                socket_id,
+                user_id,
+                ..
+            } = event
+            {
+                typed.push(ClientEvent {
+                    channel,
+                    event,
+                    data,
+                    socket_id,
+                    user_id,
+                });
+            }
+        }
+        Ok(typed)
+    }
+
+    /// Iterates over the events with their timestamps
+    ///
+    /// An event's own `time_ms` field is used when present, falling back to the webhook's
+    /// `time_ms`. Yields nothing if the body is invalid.
+    pub fn events(&self) -> impl Iterator<Item = (WebhookEvent, SystemTime)> + '_ {
+        let (time_ms, raw_events) = match &self.data {
+            Some(data) => (data.time_ms, data.events.as_slice()),
+            None => (0, &[][..]),
+        };
+        raw_events.iter().map(move |raw| {
+            let event_time_ms = raw
+                .get("time_ms")
+                .and_then(|t| t.parse::<i64>().ok())
+                .unwrap_or(time_ms);
+            (
+                parse_webhook_event(raw),
+                UNIX_EPOCH + Duration::from_millis(event_time_ms.max(0) as u64),
+            )
+        })
+    }
+
+    /// Calls the matching handler method for every event, in order
+    pub fn dispatch<H: WebhookHandler + ?Sized>(&self, handler: &mut H) -> Result<()> {
+        for event in self.get_events()? {
+            event.dispatch(handler);
+        }
+        Ok(())
+    }
+
     /// Gets the timestamp from webhook data
     pub fn get_time(&self) -> Result<std::time::SystemTime> {
         let time_ms = self.get_data()?.time_ms;
@@ -155,7 +429,7 @@ impl Webhook {
             return Err(PusherError::Webhook(WebhookError::new(
                 "Invalid negative timestamp",
                 self.content_type.clone(),
-                &self.body,
+                String::from_utf8_lossy(&self.body),
                 self.signature.clone(),
             )));
         }
@@ -163,8 +437,15 @@ impl Webhook {
         Ok(std::time::UNIX_EPOCH + duration)
     }
 
-    /// Gets the raw body
-    pub fn body(&self) -> &str {
+    /// Gets the body as UTF-8, or `None` if it is not valid UTF-8
+    ///
+    /// Use [`body_bytes`](Self::body_bytes) for the exact bytes that were signed.
+    pub fn body(&self) -> Option<&str> {
+        std::str::from_utf8(&self.body).ok()
+    }
+
+    /// Gets the exact raw body bytes
+    pub fn body_bytes(&self) -> &[u8] {
         &self.body
     }
 
@@ -195,6 +476,15 @@ impl Webhook {
             .filter(|e| e.channel() == Some(channel))
             .collect())
     }
+
+    /// Finds events whose channel matches the pattern
+    pub fn find_events_by_pattern(&self, pattern: &ChannelPattern) -> Result<Vec<WebhookEvent>> {
+        let events = self.get_events()?;
+        Ok(events
+            .into_iter()
+            .filter(|e| e.channel().is_some_and(|channel| pattern.matches(channel)))
+            .collect())
+    }
 }
 
 /// Parses a raw webhook event into a strongly typed event
@@ -266,6 +556,19 @@ fn parse_webhook_event(raw: &HashMap<String, String>) -> WebhookEvent {
                 WebhookEvent::Unknown(raw.clone())
             }
         }
+        Some("subscription_count") => {
+            let count = raw
+                .get("subscription_count")
+                .and_then(|c| c.parse::<u64>().ok());
+            if let (Some(channel), Some(subscription_count)) = (raw.get("channel"), count) {
+                WebhookEvent::SubscriptionCount {
+                    channel: channel.clone(),
+                    subscription_count,
+                }
+            } else {
+                WebhookEvent::Unknown(raw.clone())
+            }
+        }
         _ => WebhookEvent::Unknown(raw.clone()),
     }
 }
@@ -280,6 +583,7 @@ impl WebhookEvent {
             WebhookEvent::MemberRemoved { .. } => "member_removed",
             WebhookEvent::ClientEvent { .. } => "client_event",
             WebhookEvent::CacheMiss { .. } => "cache_miss",
+            WebhookEvent::SubscriptionCount { .. } => "subscription_count",
             WebhookEvent::Unknown(map) => map.get("name").map(|s| s.as_str()).unwrap_or("unknown"),
         }
     }
@@ -292,7 +596,8 @@ impl WebhookEvent {
             | WebhookEvent::MemberAdded { channel, .. }
             | WebhookEvent::MemberRemoved { channel, .. }
             | WebhookEvent::ClientEvent { channel, .. }
-            | WebhookEvent::CacheMiss { channel, .. } => Some(channel),
+            | WebhookEvent::CacheMiss { channel, .. }
+            | WebhookEvent::SubscriptionCount { channel, .. } => Some(channel),
             WebhookEvent::Unknown(map) => map.get("channel").map(|s| s.as_str()),
         }
     }
@@ -308,6 +613,97 @@ impl WebhookEvent {
         }
     }
 
+    /// Gets the member of a `member_added`/`member_removed` event
+    ///
+    /// Webhooks carry only the user ID, so `user_info` is `None`; look it up by ID if needed.
+    pub fn presence_member<T>(&self) -> Option<crate::auth::PresenceMember<T>> {
+        match self {
+            WebhookEvent::MemberAdded { user_id, .. }
+            | WebhookEvent::MemberRemoved { user_id, .. } => {
+                Some(crate::auth::PresenceMember::without_info(user_id.clone()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Calls the handler method matching this event
+    pub fn dispatch<H: WebhookHandler + ?Sized>(&self, handler: &mut H) {
+        match self {
+            WebhookEvent::ChannelOccupied { channel } => handler.on_channel_occupied(channel),
+            WebhookEvent::ChannelVacated { channel } => handler.on_channel_vacated(channel),
+            WebhookEvent::MemberAdded { channel, user_id } => {
+                handler.on_member_added(channel, user_id)
+            }
+            WebhookEvent::MemberRemoved { channel, user_id } => {
+                handler.on_member_removed(channel, user_id)
+            }
+            WebhookEvent::ClientEvent {
+                channel,
+                event,
+                data,
+                socket_id,
+                user_id,
+            } => handler.on_client_event(channel, event, data, socket_id, user_id.as_deref()),
+            WebhookEvent::CacheMiss { channel, event } => handler.on_cache_miss(channel, event),
+            WebhookEvent::SubscriptionCount {
+                channel,
+                subscription_count,
+            } => handler.on_subscription_count(channel, *subscription_count),
+            WebhookEvent::Unknown(raw) => handler.on_unknown(raw),
+        }
+    }
+
+    /// Gets the plaintext `data` of a client event, decrypting it on encrypted channels
+    pub fn decrypt_data(&self, pusher: &Pusher) -> Result<String> {
+        match self {
+            WebhookEvent::ClientEvent { channel, data, .. } => {
+                if util::is_encrypted_channel(channel) {
+                    events::decrypt(pusher, channel, data)
+                } else {
+                    Ok(data.clone())
+                }
+            }
+            _ => Err(PusherError::Validation {
+                message: format!("Event {} has no data", self.event_name()),
+            }),
+        }
+    }
+
+    /// Decodes the base64 `data` of a client event sent as binary
+    ///
+    /// On encrypted channels, decode the output of [`WebhookEvent::decrypt_data`] with
+    /// [`EventData::to_bytes`](crate::events::EventData::to_bytes) instead.
+    pub fn binary_data(&self) -> Result<Vec<u8>> {
+        match self {
+            WebhookEvent::ClientEvent { data, .. } => {
+                crate::events::EventData::from_string(data.as_str()).to_bytes()
+            }
+            _ => Err(PusherError::Validation {
+                message: format!("Event {} has no data", self.event_name()),
+            }),
+        }
+    }
+
+    /// Deserializes the `data` of a client event into `T`
+    pub fn data_as<T: DeserializeOwned>(&self) -> Result<T> {
+        match self {
+            WebhookEvent::ClientEvent {
+                channel,
+                event,
+                data,
+                ..
+            } => json::from_str(data).map_err(|e| PusherError::Validation {
+                message: format!(
+                    "Invalid data for client event {} on channel {}: {}",
+                    event, channel, e
+                ),
+            }),
+            _ => Err(PusherError::Validation {
+                message: format!("Event {} has no data", self.event_name()),
+            }),
+        }
+    }
+
     /// Converts the event back to a HashMap
     pub fn to_hashmap(&self) -> HashMap<String, String> {
         let mut map = HashMap::new();
@@ -352,6 +748,17 @@ impl WebhookEvent {
                 map.insert("channel".to_string(), channel.clone());
                 map.insert("event".to_string(), event.clone());
             }
+            WebhookEvent::SubscriptionCount {
+                channel,
+                subscription_count,
+            } => {
+                map.insert("name".to_string(), "subscription_count".to_string());
+                map.insert("channel".to_string(), channel.clone());
+                map.insert(
+                    "subscription_count".to_string(),
+                    subscription_count.to_string(),
+                );
+            }
             WebhookEvent::Unknown(original) => {
                 return original.clone();
             }
@@ -361,6 +768,154 @@ impl WebhookEvent {
     }
 }
 
+/// Allowed clock skew between a webhook's `time_ms` and the local clock
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tolerance(Duration);
+
+impl Tolerance {
+    /// Creates a tolerance from a duration
+    pub fn new(window: Duration) -> Self {
+        Self(window)
+    }
+
+    /// Creates a tolerance of the given number of seconds
+    pub fn from_secs(secs: u64) -> Self {
+        Self(Duration::from_secs(secs))
+    }
+
+    /// Gets the allowed window
+    pub fn window(&self) -> Duration {
+        self.0
+    }
+
+    /// Checks whether a timestamp is within the window around `now`
+    fn contains(&self, time_ms: i64, now: SystemTime) -> bool {
+        let now_ms = now
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as i64;
+        u128::from(now_ms.abs_diff(time_ms)) <= self.0.as_millis()
+    }
+}
+
+/// Typed callbacks for webhook events, used with [`Webhook::dispatch`]
+///
+/// Every method defaults to a no-op, so implementors only override the events they care about.
+#[allow(unused_variables)]
+pub trait WebhookHandler {
+    fn on_channel_occupied(&mut self, channel: &str) {}
+
+    fn on_channel_vacated(&mut self, channel: &str) {}
+
+    fn on_member_added(&mut self, channel: &str, user_id: &str) {}
+
+    fn on_member_removed(&mut self, channel: &str, user_id: &str) {}
+
+    fn on_client_event(
+        &mut self,
+        channel: &str,
+        event: &str,
+        data: &str,
+        socket_id: &str,
+        user_id: Option<&str>,
+    ) {
+    }
+
+    fn on_cache_miss(&mut self, channel: &str, event: &str) {}
+
+    fn on_subscription_count(&mut self, channel: &str, subscription_count: u64) {}
+
+    /// Called for events this library does not recognise
+    fn on_unknown(&mut self, event: &HashMap<String, String>) {}
+}
+
+/// A webhook whose content type, body, and signature have been verified
+#[derive(Debug, Clone, Copy)]
+pub struct ValidatedWebhook<'a> {
+    webhook: &'a Webhook,
+    data: &'a WebhookData,
+}
+
+impl<'a> ValidatedWebhook<'a> {
+    /// Gets the parsed webhook data
+    pub fn data(&self) -> &'a WebhookData {
+        self.data
+    }
+
+    /// Gets the underlying webhook
+    pub fn webhook(&self) -> &'a Webhook {
+        self.webhook
+    }
+}
+
+impl Deref for ValidatedWebhook<'_> {
+    type Target = Webhook;
+
+    fn deref(&self) -> &Webhook {
+        self.webhook
+    }
+}
+
+/// Async lookup from an app key to the token that signs its webhooks
+type TokenLookup = dyn Fn(String) -> BoxFuture<'static, Option<Token>> + Send + Sync;
+
+/// Verifies webhooks for many apps, resolving the signing token from the `X-Pusher-Key` header
+///
+/// Unlike [`Webhook::new`], this does not need a `Pusher` client, which suits multi-tenant
+/// receivers that load app secrets from a database.
+#[derive(Clone)]
+pub struct WebhookVerifier {
+    lookup: Arc<TokenLookup>,
+}
+
+impl fmt::Debug for WebhookVerifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebhookVerifier").finish_non_exhaustive()
+    }
+}
+
+impl WebhookVerifier {
+    /// Creates a verifier from an async `key -> Option<Token>` lookup
+    pub fn new<F, Fut>(lookup: F) -> Self
+    where
+        F: Fn(String) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Option<Token>> + Send + 'static,
+    {
+        Self {
+            lookup: Arc::new(move |key| Box::pin(lookup(key))),
+        }
+    }
+
+    /// Looks up the token for the webhook's key and verifies its signature over the raw body
+    pub async fn verify(&self, headers: &BTreeMap<String, String>, body: &[u8]) -> Result<Webhook> {
+        let header = |name: &str| {
+            headers
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(name))
+                .map(|(_, v)| v.clone())
+        };
+        let error = |message: &str| {
+            PusherError::Webhook(WebhookError::new(
+                message,
+                header("content-type"),
+                String::from_utf8_lossy(body),
+                header("x-pusher-signature"),
+            ))
+        };
+
+        let key = header("x-pusher-key").ok_or_else(|| error("Missing X-Pusher-Key header"))?;
+        let token = (self.lookup)(key)
+            .await
+            .ok_or_else(|| error("Unknown webhook key"))?;
+
+        let webhook = Webhook::from_bytes(&token, headers, body);
+        if let Err(reason) = webhook.validate(None) {
+            return Err(error(&reason.to_string()));
+        }
+        Ok(webhook)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -374,7 +929,7 @@ mod tests {
             ]
         }"#;
 
-        let data: WebhookData = sonic_rs::from_str(json_str).unwrap();
+        let data: WebhookData = json::from_str(json_str).unwrap();
         assert_eq!(data.time_ms, 1234567890);
         assert_eq!(data.events.len(), 2);
         assert_eq!(
@@ -383,6 +938,223 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_subscription_count_event() {
+        let json_str = r#"{
+            "time_ms": 1234567890,
+            "events": [
+                {"name": "subscription_count", "channel": "test-channel", "subscription_count": 42}
+            ]
+        }"#;
+
+        let data: WebhookData = json::from_str(json_str).unwrap();
+        let event = parse_webhook_event(&data.events[0]);
+        assert_eq!(
+            event,
+            WebhookEvent::SubscriptionCount {
+                channel: "test-channel".to_string(),
+                subscription_count: 42,
+            }
+        );
+        assert_eq!(event.event_name(), "subscription_count");
+        assert_eq!(parse_webhook_event(&event.to_hashmap()), event);
+    }
+
+    #[test]
+    fn test_client_event_data_as() {
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Message {
+            text: String,
+        }
+
+        let json_str = r#"{
+            "time_ms": 1234567890,
+            "events": [
+                {"name": "channel_occupied", "channel": "private-chat"},
+                {"name": "client_event", "channel": "private-chat", "event": "client-message",
+                 "data": "{\"text\":\"hi\"}", "socket_id": "123.456"}
+            ]
+        }"#;
+
+        let token = Token::new("test_key", "test_secret");
+        let mut headers = BTreeMap::new();
+        headers.insert("content-type".to_string(), "application/json".to_string());
+        let webhook = Webhook::new(&token, &headers, json_str);
+
+        let events = webhook.get_events_typed::<Message>().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            events[0].data,
+            Message {
+                text: "hi".to_string()
+            }
+        );
+        assert_eq!(events[0].event, "client-message");
+
+        let occupied = &webhook.get_events().unwrap()[0];
+        assert!(occupied.data_as::<Message>().is_err());
+        assert!(webhook.get_events_typed::<u32>().is_err());
+    }
+
+    #[test]
+    fn test_client_event_binary_data() {
+        let event = WebhookEvent::ClientEvent {
+            channel: "private-chat".to_string(),
+            event: "client-frame".to_string(),
+            data: "CJYB/w==".to_string(),
+            socket_id: "123.456".to_string(),
+            user_id: None,
+        };
+        assert_eq!(event.binary_data().unwrap(), vec![0x08, 0x96, 0x01, 0xff]);
+
+        let occupied = WebhookEvent::ChannelOccupied {
+            channel: "private-chat".to_string(),
+        };
+        assert!(occupied.binary_data().is_err());
+    }
+
+    #[test]
+    fn test_webhook_event_serde() {
+        let event = WebhookEvent::ClientEvent {
+            channel: "private-chat".to_string(),
+            event: "client-message".to_string(),
+            data: "{}".to_string(),
+            socket_id: "123.456".to_string(),
+            user_id: None,
+        };
+        let value = json::to_value(&event).unwrap();
+        assert_eq!(
+            value,
+            json::json!({
+                "name": "client_event",
+                "channel": "private-chat",
+                "event": "client-message",
+                "data": "{}",
+                "socket_id": "123.456"
+            })
+        );
+
+        let count: WebhookEvent = json::from_str(
+            r#"{"name": "subscription_count", "channel": "test", "subscription_count": 3}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            count,
+            WebhookEvent::SubscriptionCount {
+                channel: "test".to_string(),
+                subscription_count: 3,
+            }
+        );
+
+        let unknown: WebhookEvent =
+            json::from_str(r#"{"name": "new_event", "channel": "test"}"#).unwrap();
+        assert_eq!(unknown.event_name(), "new_event");
+        let roundtrip: WebhookEvent = json::from_str(&json::to_string(&unknown).unwrap()).unwrap();
+        assert_eq!(roundtrip, unknown);
+    }
+
+    #[test]
+    fn test_events_iterator_timestamps() {
+        let json_str = r#"{
+            "time_ms": 2000,
+            "events": [
+                {"name": "channel_occupied", "channel": "a"},
+                {"name": "channel_vacated", "channel": "a", "time_ms": 1500}
+            ]
+        }"#;
+
+        let token = Token::new("test_key", "test_secret");
+        let mut headers = BTreeMap::new();
+        headers.insert("content-type".to_string(), "application/json".to_string());
+        let webhook = Webhook::new(&token, &headers, json_str);
+
+        let events: Vec<_> = webhook.events().collect();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].1, UNIX_EPOCH + Duration::from_millis(2000));
+        assert_eq!(events[1].0.event_name(), "channel_vacated");
+        assert_eq!(events[1].1, UNIX_EPOCH + Duration::from_millis(1500));
+    }
+
+    #[test]
+    fn test_find_events_by_pattern() {
+        let json_str = r#"{
+            "time_ms": 2000,
+            "events": [
+                {"name": "channel_occupied", "channel": "presence-game-1"},
+                {"name": "channel_occupied", "channel": "presence-lobby"},
+                {"name": "channel_vacated", "channel": "presence-game-2"}
+            ]
+        }"#;
+
+        let token = Token::new("test_key", "test_secret");
+        let mut headers = BTreeMap::new();
+        headers.insert("content-type".to_string(), "application/json".to_string());
+        let webhook = Webhook::new(&token, &headers, json_str);
+
+        let events = webhook
+            .find_events_by_pattern(&ChannelPattern::prefix("presence-game-"))
+            .unwrap();
+        let channels: Vec<_> = events.iter().filter_map(|e| e.channel()).collect();
+        assert_eq!(channels, vec!["presence-game-1", "presence-game-2"]);
+    }
+
+    #[test]
+    fn test_webhook_with_rotated_tokens() {
+        let old = Token::new("test_key", "old_secret");
+        let new = Token::new("test_key", "new_secret");
+        let body = r#"{"time_ms": 1234567890, "events": []}"#;
+
+        let mut headers = BTreeMap::new();
+        headers.insert("content-type".to_string(), "application/json".to_string());
+        headers.insert("x-pusher-key".to_string(), "test_key".to_string());
+        headers.insert("x-pusher-signature".to_string(), old.sign(body));
+
+        let tokens = [new.clone(), old];
+        assert!(Webhook::with_tokens(&tokens, &headers, body.as_bytes()).is_valid(None));
+        assert!(!Webhook::new(&new, &headers, body).is_valid(None));
+    }
+
+    #[test]
+    fn test_webhook_dispatch() {
+        #[derive(Default)]
+        struct Recorder {
+            occupied: Vec<String>,
+            members: Vec<(String, String)>,
+        }
+
+        impl WebhookHandler for Recorder {
+            fn on_channel_occupied(&mut self, channel: &str) {
+                self.occupied.push(channel.to_string());
+            }
+
+            fn on_member_added(&mut self, channel: &str, user_id: &str) {
+                self.members
+                    .push((channel.to_string(), user_id.to_string()));
+            }
+        }
+
+        let json_str = r#"{
+            "time_ms": 1234567890,
+            "events": [
+                {"name": "channel_occupied", "channel": "test-channel"},
+                {"name": "channel_vacated", "channel": "test-channel"},
+                {"name": "member_added", "channel": "presence-channel", "user_id": "user123"}
+            ]
+        }"#;
+        let token = Token::new("test_key", "test_secret");
+        let mut headers = BTreeMap::new();
+        headers.insert("content-type".to_string(), "application/json".to_string());
+        let webhook = Webhook::new(&token, &headers, json_str);
+
+        let mut recorder = Recorder::default();
+        webhook.dispatch(&mut recorder).unwrap();
+        assert_eq!(recorder.occupied, vec!["test-channel"]);
+        assert_eq!(
+            recorder.members,
+            vec![("presence-channel".to_string(), "user123".to_string())]
+        );
+    }
+
     #[test]
     fn test_webhook_validation() {
         let token = Token::new("test_key", "test_secret");
@@ -398,6 +1170,147 @@ mod tests {
         assert!(webhook.is_valid(None));
     }
 
+    #[test]
+    fn test_webhook_from_bytes() {
+        let token = Token::new("test_key", "test_secret");
+        let body = b"{\"time_ms\": 1234567890, \"events\": []}\r\n".to_vec();
+
+        let mut headers = BTreeMap::new();
+        headers.insert("content-type".to_string(), "application/json".to_string());
+        headers.insert("x-pusher-key".to_string(), "test_key".to_string());
+        headers.insert(
+            "x-pusher-signature".to_string(),
+            hex::encode(token.sign_bytes(&body)),
+        );
+
+        let webhook = Webhook::from_bytes(&token, &headers, &body);
+        assert_eq!(webhook.body_bytes(), body.as_slice());
+        assert_eq!(webhook.body().map(str::as_bytes), Some(body.as_slice()));
+        assert!(webhook.is_valid(None));
+
+        let binary = Webhook::from_bytes(&token, &headers, b"\xff\xfe");
+        assert_eq!(binary.body(), None);
+        assert_eq!(binary.body_bytes(), b"\xff\xfe");
+    }
+
+    #[test]
+    fn test_webhook_validation_reasons() {
+        let token = Token::new("test_key", "test_secret");
+        let body = r#"{"time_ms": 1234567890, "events": []}"#;
+
+        let mut headers = BTreeMap::new();
+        headers.insert("content-type".to_string(), "text/plain".to_string());
+        let webhook = Webhook::new(&token, &headers, body);
+        assert_eq!(
+            webhook.validate(None).unwrap_err(),
+            WebhookValidationError::InvalidContentType(Some("text/plain".to_string()))
+        );
+
+        headers.insert("content-type".to_string(), "application/json".to_string());
+        headers.insert("x-pusher-key".to_string(), "other_key".to_string());
+        headers.insert("x-pusher-signature".to_string(), token.sign(body));
+        let webhook = Webhook::new(&token, &headers, body);
+        assert_eq!(
+            webhook.validate(None).unwrap_err(),
+            WebhookValidationError::UnknownKey("other_key".to_string())
+        );
+
+        headers.insert("x-pusher-key".to_string(), "test_key".to_string());
+        headers.insert("x-pusher-signature".to_string(), "bad".to_string());
+        let webhook = Webhook::new(&token, &headers, body);
+        assert_eq!(
+            webhook.validate(None).unwrap_err(),
+            WebhookValidationError::SignatureMismatch
+        );
+
+        headers.insert("x-pusher-signature".to_string(), token.sign(body));
+        let webhook = Webhook::new(&token, &headers, body);
+        let validated = webhook.validate(None).unwrap();
+        assert_eq!(validated.data().time_ms, 1234567890);
+        assert_eq!(validated.key(), Some("test_key"));
+    }
+
+    #[test]
+    fn test_webhook_tolerance() {
+        let token = Token::new("test_key", "test_secret");
+        let now_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis();
+
+        let webhook_at = |time_ms: u128| {
+            let body = format!(r#"{{"time_ms": {}, "events": []}}"#, time_ms);
+            let mut headers = BTreeMap::new();
+            headers.insert("content-type".to_string(), "application/json".to_string());
+            headers.insert("x-pusher-key".to_string(), "test_key".to_string());
+            headers.insert("x-pusher-signature".to_string(), token.sign(&body));
+            Webhook::new(&token, &headers, &body)
+        };
+
+        let tolerance = Tolerance::from_secs(300);
+        assert!(webhook_at(now_ms).validate_with(tolerance).is_ok());
+        assert_eq!(
+            webhook_at(now_ms - 600_000)
+                .validate_with(tolerance)
+                .unwrap_err(),
+            WebhookValidationError::OutsideTolerance {
+                time_ms: (now_ms - 600_000) as i64
+            }
+        );
+    }
+
+    #[test]
+    fn test_webhook_tolerance_uses_clock() {
+        let token = Token::new("test_key", "test_secret");
+        let body = r#"{"time_ms": 1234567890, "events": []}"#;
+
+        let mut headers = BTreeMap::new();
+        headers.insert("content-type".to_string(), "application/json".to_string());
+        headers.insert("x-pusher-key".to_string(), "test_key".to_string());
+        headers.insert("x-pusher-signature".to_string(), token.sign(body));
+
+        let clock = crate::clock::FixedClock::from_unix_secs(1_234_600);
+        let webhook = Webhook::new(&token, &headers, body).with_clock(Arc::new(clock));
+        assert!(webhook.validate_with(Tolerance::from_secs(60)).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_webhook_verifier_lookup() {
+        let verifier = WebhookVerifier::new(|key| async move {
+            (key == "tenant_key").then(|| Token::new("tenant_key", "tenant_secret"))
+        });
+        let body = r#"{"time_ms": 1234567890, "events": []}"#;
+
+        let mut headers = BTreeMap::new();
+        headers.insert("Content-Type".to_string(), "application/json".to_string());
+        headers.insert("X-Pusher-Key".to_string(), "tenant_key".to_string());
+        headers.insert(
+            "X-Pusher-Signature".to_string(),
+            Token::new("tenant_key", "tenant_secret").sign(body),
+        );
+        assert!(verifier.verify(&headers, body.as_bytes()).await.is_ok());
+
+        headers.insert("X-Pusher-Key".to_string(), "other_key".to_string());
+        assert!(verifier.verify(&headers, body.as_bytes()).await.is_err());
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn test_webhook_from_http_request() {
+        let token = Token::new("test_key", "test_secret");
+        let body = r#"{"time_ms": 1234567890, "events": []}"#;
+
+        let request = http::Request::builder()
+            .header("Content-Type", "application/json")
+            .header("X-Pusher-Key", "test_key")
+            .header("X-Pusher-Signature", token.sign(body))
+            .body(bytes::Bytes::from(body))
+            .unwrap();
+
+        let webhook = Webhook::from_http_request(&token, &request);
+        assert!(webhook.is_valid(None));
+    }
+
     #[test]
     fn test_event_parsing() {
         let mut event_map = HashMap::new();
@@ -421,4 +1334,19 @@ mod tests {
 
         assert_eq!(event, parsed);
     }
+
+    #[test]
+    fn test_presence_member() {
+        let event = WebhookEvent::MemberRemoved {
+            channel: "presence-test".to_string(),
+            user_id: "user123".to_string(),
+        };
+        let member = event.presence_member::<crate::json::Value>().unwrap();
+        assert_eq!(member, crate::auth::PresenceMember::new("user123"));
+
+        let occupied = WebhookEvent::ChannelOccupied {
+            channel: "presence-test".to_string(),
+        };
+        assert!(occupied.presence_member::<crate::json::Value>().is_none());
+    }
 }